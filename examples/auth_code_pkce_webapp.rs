@@ -0,0 +1,78 @@
+//! In a web server, the authorization URL and the access token are usually
+//! requested in different HTTP requests, and possibly even in different
+//! processes. Since PKCE requires the same code verifier in both steps, it has
+//! to be stored somewhere in the meantime, like in the user's session.
+//!
+//! This example simulates both handlers of such a web server, with a
+//! `HashMap` acting as the session storage. Each handler builds its own
+//! client, as it would happen in a real web app.
+
+use rspotify::{prelude::*, scopes, AuthCodePkceSpotify, Credentials, OAuth};
+
+use std::collections::HashMap;
+
+/// The sessions of the users, where the code verifier is saved while they
+/// authorize the app. A real web app would use its framework's session
+/// storage or a database instead.
+type Sessions = HashMap<String, String>;
+
+fn init_spotify() -> AuthCodePkceSpotify {
+    // Set RSPOTIFY_CLIENT_ID and RSPOTIFY_REDIRECT_URI in an .env file or
+    // export them manually. See the `auth_code_pkce` example for more
+    // information.
+    let creds = Credentials::from_env().unwrap();
+    let oauth = OAuth::from_env(scopes!("user-read-recently-played")).unwrap();
+
+    AuthCodePkceSpotify::new(creds, oauth)
+}
+
+/// The handler for the index page, which redirects the user to Spotify's
+/// authorization page.
+fn index(sessions: &mut Sessions, session_id: &str) -> String {
+    let spotify = init_spotify();
+    let (url, verifier) = spotify.get_authorize_url_with_verifier(false).unwrap();
+    sessions.insert(session_id.to_owned(), verifier);
+
+    url
+}
+
+/// The handler for the redirect URI, which Spotify calls with the code once
+/// the user has authorized the app.
+async fn callback(sessions: &mut Sessions, session_id: &str, code: &str) -> AuthCodePkceSpotify {
+    // The verifier is only valid for a single token request
+    let verifier = sessions.remove(session_id).unwrap();
+
+    let mut spotify = init_spotify();
+    spotify
+        .request_token_with_verifier(code, &verifier)
+        .await
+        .unwrap();
+
+    spotify
+}
+
+#[tokio::main]
+async fn main() {
+    // You can use any logger for debugging.
+    env_logger::init();
+
+    let mut sessions = Sessions::new();
+    let session_id = "some-user-session";
+
+    let url = index(&mut sessions, session_id);
+    println!("Open this URL in your browser:\n{}\n", url);
+
+    // This is what the web server would receive in the callback request
+    println!("Please enter the URL you were redirected to:");
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input).unwrap();
+    let code = init_spotify()
+        .parse_response_code_and_verify_state(input.trim())
+        .unwrap();
+
+    let spotify = callback(&mut sessions, session_id, &code).await;
+
+    // Running the requests
+    let history = spotify.current_playback(None, None::<Vec<_>>).await;
+    println!("Response: {:?}", history);
+}
@@ -13,7 +13,7 @@ fn main() {
     let url = spotify.get_authorize_url(false).unwrap();
     spotify.prompt_for_token(&url).unwrap();
 
-    match spotify.seek_track(25000, None) {
+    match spotify.seek_position(25000, None) {
         Ok(_) => println!("change to previous playback successful"),
         Err(_) => eprintln!("change to previous playback failed"),
     }
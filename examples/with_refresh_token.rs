@@ -33,7 +33,7 @@ async fn do_things(spotify: AuthCodeSpotify) {
 
     // Printing the followed artists
     let followed = spotify
-        .current_user_followed_artists(None, None)
+        .current_user_followed_artists_manual(None, None)
         .await
         .expect("couldn't get user followed artists");
     println!(
@@ -79,7 +79,7 @@ async fn main() {
     // At a different time, the refresh token can be used to refresh an access
     // token directly and run requests:
     println!(">>> Session two, running some requests:");
-    let mut spotify = AuthCodeSpotify::new(creds.clone(), oauth.clone());
+    let mut spotify = AuthCodeSpotify::new(creds.clone(), oauth);
     // No `prompt_for_user_token_without_cache` needed.
     spotify
         .refresh_token(&refresh_token)
@@ -88,11 +88,10 @@ async fn main() {
     do_things(spotify).await;
 
     // This process can now be repeated multiple times by using only the
-    // refresh token that was obtained at the beginning.
+    // refresh token that was obtained at the beginning. The client can also
+    // be built and refreshed in a single step:
     println!(">>> Session three, running some requests:");
-    let mut spotify = AuthCodeSpotify::new(creds, oauth);
-    spotify
-        .refresh_token(&refresh_token)
+    let spotify = AuthCodeSpotify::from_refresh_token(creds, &refresh_token)
         .await
         .expect("couldn't refresh user token");
     do_things(spotify).await;
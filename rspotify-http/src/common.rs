@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 
 use maybe_async::maybe_async;
 use rspotify_model::ApiError;
@@ -20,6 +22,12 @@ pub enum HttpError {
     #[error("request error: {0}")]
     Request(String),
 
+    /// The connection failed before a response could be received, e.g.
+    /// because the host couldn't be resolved or the connection was reset.
+    /// These errors are usually transient.
+    #[error("connection error: {0}")]
+    Connection(String),
+
     #[error("status code {0}: {1}")]
     StatusCode(u16, String),
 
@@ -32,6 +40,68 @@ pub enum HttpError {
 
 pub type HttpResult<T> = Result<T, HttpError>;
 
+/// Additional settings for a request, which are usually taken from the
+/// configuration of the Spotify client.
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// The maximum time the whole request may take, or `None` to wait
+    /// indefinitely.
+    pub timeout: Option<Duration>,
+
+    /// Invoked after receiving the response, before its body is parsed.
+    pub on_response: Option<ResponseHook>,
+
+    /// The `User-Agent` header sent with the request, or `None` to use the
+    /// default one of the HTTP client.
+    pub user_agent: Option<String>,
+
+    /// Additional headers sent with the request, unless they have been set
+    /// already, like the authorization or the user agent. The `Content-Type`
+    /// header is always ignored, since it depends on the payload.
+    pub default_headers: Headers,
+}
+
+impl RequestOptions {
+    /// The default headers that should be added to a request, given a function
+    /// that checks if a header has been set already.
+    pub(crate) fn missing_default_headers<F>(&self, is_set: F) -> Vec<(&String, &String)>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.default_headers
+            .iter()
+            .filter(|(key, _)| !key.eq_ignore_ascii_case("content-type") && !is_set(key))
+            .collect()
+    }
+}
+
+/// A callback that receives the status code and headers of a response, which
+/// is useful for logging and observability. The names of the headers are in
+/// lowercase.
+#[derive(Clone)]
+pub struct ResponseHook(Arc<ResponseHookFn>);
+
+type ResponseHookFn = dyn Fn(u16, &Headers) + Send + Sync;
+
+impl ResponseHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(u16, &Headers) + Send + Sync + 'static,
+    {
+        ResponseHook(Arc::new(hook))
+    }
+
+    pub fn call(&self, status: u16, headers: &Headers) {
+        (self.0)(status, headers)
+    }
+}
+
+impl fmt::Debug for ResponseHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ResponseHook")
+    }
+}
+
 /// This trait represents the interface to be implemented for an HTTP client,
 /// which is kept separate from the Spotify client for cleaner code. Thus, it
 /// also requires other basic traits that are needed for the Spotify client.
@@ -42,7 +112,11 @@ pub type HttpResult<T> = Result<T, HttpError>;
 /// different ways (`Value::Null`, an empty `Value::Object`...), so this removes
 /// redundancy and edge cases (a `Some(Value::Null), for example, doesn't make
 /// much sense).
-#[maybe_async]
+///
+/// The futures of the browser's fetch API aren't `Send`, so they aren't
+/// required to be on WebAssembly.
+#[cfg_attr(target_arch = "wasm32", maybe_async(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), maybe_async)]
 pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
     // This internal function should always be given an object value in JSON.
     async fn get(
@@ -50,6 +124,7 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         url: &str,
         headers: Option<&Headers>,
         payload: &Query,
+        options: &RequestOptions,
     ) -> HttpResult<String>;
 
     async fn post(
@@ -57,6 +132,7 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        options: &RequestOptions,
     ) -> HttpResult<String>;
 
     async fn post_form<'a>(
@@ -64,6 +140,7 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         url: &str,
         headers: Option<&Headers>,
         payload: &Form<'a>,
+        options: &RequestOptions,
     ) -> HttpResult<String>;
 
     async fn put(
@@ -71,6 +148,7 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        options: &RequestOptions,
     ) -> HttpResult<String>;
 
     async fn delete(
@@ -78,5 +156,28 @@ pub trait BaseHttpClient: Send + Default + Clone + fmt::Debug {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        options: &RequestOptions,
+    ) -> HttpResult<String>;
+
+    /// Same as [`Self::post`], but the body is sent as is, with the given
+    /// content type, instead of as JSON.
+    async fn post_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+        options: &RequestOptions,
+    ) -> HttpResult<String>;
+
+    /// Same as [`Self::put`], but the body is sent as is, with the given
+    /// content type, instead of as JSON.
+    async fn put_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+        options: &RequestOptions,
     ) -> HttpResult<String>;
 }
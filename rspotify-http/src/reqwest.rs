@@ -1,12 +1,15 @@
 //! The client implementation for the reqwest HTTP client, which is async by
 //! default.
 
-use super::{BaseHttpClient, Form, Headers, HttpError, HttpResult, Query};
+use super::{BaseHttpClient, Form, Headers, HttpError, HttpResult, Query, RequestOptions};
 
 use std::convert::TryInto;
 
 use maybe_async::async_impl;
-use reqwest::{Method, RequestBuilder, StatusCode};
+use reqwest::{
+    header::{HeaderName, HeaderValue, CONTENT_TYPE},
+    Method, RequestBuilder, StatusCode,
+};
 use rspotify_model::ApiError;
 use serde_json::Value;
 
@@ -21,22 +24,40 @@ impl HttpError {
                     .and_then(|header| header.to_str().ok())
                     .and_then(|duration| duration.parse().ok()),
             ),
-            status @ StatusCode::FORBIDDEN | status @ StatusCode::NOT_FOUND => response
+            status => response
                 .json::<ApiError>()
                 .await
                 .map(Into::into)
                 .unwrap_or_else(|_| status.into()),
-            status => status.into(),
         }
     }
 }
 
 impl From<reqwest::Error> for HttpError {
     fn from(err: reqwest::Error) -> Self {
-        Self::Request(err.to_string())
+        if is_connection_error(&err) {
+            Self::Connection(err.to_string())
+        } else {
+            Self::Request(err.to_string())
+        }
     }
 }
 
+/// Whether the request failed because of the connection, rather than because
+/// of the request itself. Timeouts are configured by the user, so they aren't
+/// considered connection errors.
+#[cfg(not(target_arch = "wasm32"))]
+fn is_connection_error(err: &reqwest::Error) -> bool {
+    err.is_connect() || (err.is_request() && !err.is_timeout())
+}
+
+/// The browser doesn't report connection errors separately, so all the
+/// request errors are considered as such.
+#[cfg(target_arch = "wasm32")]
+fn is_connection_error(err: &reqwest::Error) -> bool {
+    err.is_request()
+}
+
 impl From<reqwest::StatusCode> for HttpError {
     fn from(code: reqwest::StatusCode) -> Self {
         Self::StatusCode(
@@ -52,12 +73,21 @@ pub struct ReqwestClient {
     client: reqwest::Client,
 }
 
+/// Wraps an already configured client, so that its connection pool and
+/// settings are reused.
+impl From<reqwest::Client> for ReqwestClient {
+    fn from(client: reqwest::Client) -> Self {
+        ReqwestClient { client }
+    }
+}
+
 impl ReqwestClient {
     async fn request<D>(
         &self,
         method: Method,
         url: &str,
         headers: Option<&Headers>,
+        options: &RequestOptions,
         add_data: D,
     ) -> HttpResult<String>
     where
@@ -78,12 +108,47 @@ impl ReqwestClient {
             request = request.headers(headers);
         }
 
+        if let Some(user_agent) = &options.user_agent {
+            request = request.header(reqwest::header::USER_AGENT, user_agent);
+        }
+
+        // The timeout isn't supported by the browser's fetch API
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+
         // Configuring the request for the specific type (get/post/put/delete)
         request = add_data(request);
 
+        // The default headers are added last, so that the ones already set
+        // take precedence.
+        let mut request = request.build()?;
+        let default_headers = options
+            .missing_default_headers(|key| request.headers().contains_key(key))
+            .into_iter()
+            .map(|(key, val)| {
+                let key = HeaderName::from_bytes(key.as_bytes())
+                    .map_err(|err| HttpError::Request(err.to_string()))?;
+                let val = HeaderValue::from_str(val)
+                    .map_err(|err| HttpError::Request(err.to_string()))?;
+                Ok((key, val))
+            })
+            .collect::<HttpResult<Vec<_>>>()?;
+        request.headers_mut().extend(default_headers);
+
         // Finally performing the request and handling the response
         log::info!("Making request {:?}", request);
-        let response = request.send().await?;
+        let response = self.client.execute(request).await?;
+
+        if let Some(hook) = &options.on_response {
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(key, val)| Some((key.to_string(), val.to_str().ok()?.to_owned())))
+                .collect();
+            hook.call(response.status().as_u16(), &headers);
+        }
 
         if response.status().is_success() {
             response.text().await.map_err(Into::into)
@@ -93,7 +158,8 @@ impl ReqwestClient {
     }
 }
 
-#[async_impl]
+#[cfg_attr(target_arch = "wasm32", async_impl(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_impl)]
 impl BaseHttpClient for ReqwestClient {
     #[inline]
     async fn get(
@@ -101,8 +167,9 @@ impl BaseHttpClient for ReqwestClient {
         url: &str,
         headers: Option<&Headers>,
         payload: &Query,
+        options: &RequestOptions,
     ) -> HttpResult<String> {
-        self.request(Method::GET, url, headers, |req| req.query(payload))
+        self.request(Method::GET, url, headers, options, |req| req.query(payload))
             .await
     }
 
@@ -112,8 +179,9 @@ impl BaseHttpClient for ReqwestClient {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        options: &RequestOptions,
     ) -> HttpResult<String> {
-        self.request(Method::POST, url, headers, |req| req.json(payload))
+        self.request(Method::POST, url, headers, options, |req| req.json(payload))
             .await
     }
 
@@ -123,8 +191,9 @@ impl BaseHttpClient for ReqwestClient {
         url: &str,
         headers: Option<&Headers>,
         payload: &Form<'a>,
+        options: &RequestOptions,
     ) -> HttpResult<String> {
-        self.request(Method::POST, url, headers, |req| req.form(payload))
+        self.request(Method::POST, url, headers, options, |req| req.form(payload))
             .await
     }
 
@@ -134,8 +203,9 @@ impl BaseHttpClient for ReqwestClient {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        options: &RequestOptions,
     ) -> HttpResult<String> {
-        self.request(Method::PUT, url, headers, |req| req.json(payload))
+        self.request(Method::PUT, url, headers, options, |req| req.json(payload))
             .await
     }
 
@@ -145,8 +215,43 @@ impl BaseHttpClient for ReqwestClient {
         url: &str,
         headers: Option<&Headers>,
         payload: &Value,
+        options: &RequestOptions,
     ) -> HttpResult<String> {
-        self.request(Method::DELETE, url, headers, |req| req.json(payload))
-            .await
+        self.request(Method::DELETE, url, headers, options, |req| {
+            req.json(payload)
+        })
+        .await
+    }
+
+    #[inline]
+    async fn post_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
+        self.request(Method::POST, url, headers, options, |req| {
+            req.header(CONTENT_TYPE, content_type)
+                .body(payload.to_vec())
+        })
+        .await
+    }
+
+    #[inline]
+    async fn put_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
+        self.request(Method::PUT, url, headers, options, |req| {
+            req.header(CONTENT_TYPE, content_type)
+                .body(payload.to_vec())
+        })
+        .await
     }
 }
@@ -1,14 +1,44 @@
 //! The client implementation for the ureq HTTP client, which is blocking.
 
-use super::{BaseHttpClient, Form, Headers, HttpError, HttpResult, Query};
+use super::{BaseHttpClient, Form, Headers, HttpError, HttpResult, Query, RequestOptions};
 
 use maybe_async::sync_impl;
+use rspotify_model::ApiError;
 use serde_json::Value;
 use ureq::{Request, Response};
 
 impl HttpError {
     pub fn from_response(r: ureq::Response) -> Self {
-        HttpError::StatusCode(r.status(), r.status_text().to_string())
+        match r.status() {
+            401 => HttpError::Unauthorized,
+            429 => HttpError::RateLimited(
+                r.header("Retry-After")
+                    .and_then(|duration| duration.parse().ok()),
+            ),
+            status => {
+                let status_text = r.status_text().to_string();
+                r.into_json::<ApiError>()
+                    .map(Into::into)
+                    .unwrap_or(HttpError::StatusCode(status, status_text))
+            }
+        }
+    }
+}
+
+/// Whether the request failed because of the connection, rather than because
+/// of the request itself. Timeouts are configured by the user, so they aren't
+/// considered connection errors.
+fn is_connection_error(err: &ureq::Transport) -> bool {
+    use std::{error::Error, io};
+    use ureq::ErrorKind;
+
+    match err.kind() {
+        ErrorKind::Dns | ErrorKind::ConnectionFailed => true,
+        ErrorKind::Io => !matches!(
+            err.source().and_then(|source| source.downcast_ref::<io::Error>()),
+            Some(source) if matches!(source.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock)
+        ),
+        _ => false,
     }
 }
 
@@ -24,14 +54,17 @@ impl UreqClient {
     ///   for all requests.
     /// * The request is finished and performed with the `send_request` function
     ///   (JSON, a form...).
+    ///
+    /// The error is boxed because `ureq::Error` is quite large.
     fn request<D>(
         &self,
         mut request: Request,
         headers: Option<&Headers>,
+        options: &RequestOptions,
         send_request: D,
     ) -> HttpResult<String>
     where
-        D: Fn(Request) -> Result<Response, ureq::Error>,
+        D: Fn(Request) -> Result<Response, Box<ureq::Error>>,
     {
         // Setting the headers, which will be the token auth if unspecified.
         if let Some(headers) = headers {
@@ -40,13 +73,51 @@ impl UreqClient {
             }
         }
 
+        if let Some(user_agent) = &options.user_agent {
+            request = request.set("user-agent", user_agent);
+        }
+
+        if let Some(timeout) = options.timeout {
+            request = request.timeout(timeout);
+        }
+
+        // The default headers are added last, so that the ones already set
+        // take precedence.
+        let default_headers = options.missing_default_headers(|key| request.header(key).is_some());
+        for (key, val) in default_headers {
+            request = request.set(key, val);
+        }
+
         log::info!("Making request {:?}", request);
-        match send_request(request) {
+        let result = send_request(request).map_err(|err| *err);
+
+        if let Some(hook) = &options.on_response {
+            let response = match &result {
+                Ok(response) | Err(ureq::Error::Status(_, response)) => Some(response),
+                Err(_) => None,
+            };
+            if let Some(response) = response {
+                let headers = response
+                    .headers_names()
+                    .into_iter()
+                    .filter_map(|key| {
+                        let val = response.header(&key)?.to_owned();
+                        Some((key, val))
+                    })
+                    .collect();
+                hook.call(response.status(), &headers);
+            }
+        }
+
+        match result {
             // Successful request
             Ok(response) => response.into_string().map_err(Into::into),
             // HTTP status error
             Err(ureq::Error::Status(_, response)) => Err(HttpError::from_response(response)),
             // Some kind of IO/transport error
+            Err(ureq::Error::Transport(err)) if is_connection_error(&err) => {
+                Err(HttpError::Connection(err.to_string()))
+            }
             Err(err) => Err(HttpError::Request(err.to_string())),
         }
     }
@@ -55,22 +126,34 @@ impl UreqClient {
 #[sync_impl]
 impl BaseHttpClient for UreqClient {
     #[inline]
-    fn get(&self, url: &str, headers: Option<&Headers>, payload: &Query) -> HttpResult<String> {
+    fn get(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
         let request = ureq::get(url);
         let sender = |mut req: Request| {
             for (key, val) in payload.iter() {
                 req = req.query(key, val)
             }
-            req.call()
+            req.call().map_err(Box::new)
         };
-        self.request(request, headers, sender)
+        self.request(request, headers, options, sender)
     }
 
     #[inline]
-    fn post(&self, url: &str, headers: Option<&Headers>, payload: &Value) -> HttpResult<String> {
+    fn post(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
         let request = ureq::post(url);
-        let sender = |req: Request| req.send_json(payload.clone());
-        self.request(request, headers, sender)
+        let sender = |req: Request| req.send_json(payload.clone()).map_err(Box::new);
+        self.request(request, headers, options, sender)
     }
 
     #[inline]
@@ -79,6 +162,7 @@ impl BaseHttpClient for UreqClient {
         url: &str,
         headers: Option<&Headers>,
         payload: &Form<'a>,
+        options: &RequestOptions,
     ) -> HttpResult<String> {
         let request = ureq::post(url);
         let sender = |req: Request| {
@@ -87,23 +171,71 @@ impl BaseHttpClient for UreqClient {
                 .map(|(key, val)| (*key, *val))
                 .collect::<Vec<_>>();
 
-            req.send_form(&payload)
+            req.send_form(&payload).map_err(Box::new)
         };
 
-        self.request(request, headers, sender)
+        self.request(request, headers, options, sender)
     }
 
     #[inline]
-    fn put(&self, url: &str, headers: Option<&Headers>, payload: &Value) -> HttpResult<String> {
+    fn put(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
         let request = ureq::put(url);
-        let sender = |req: Request| req.send_json(payload.clone());
-        self.request(request, headers, sender)
+        let sender = |req: Request| req.send_json(payload.clone()).map_err(Box::new);
+        self.request(request, headers, options, sender)
     }
 
     #[inline]
-    fn delete(&self, url: &str, headers: Option<&Headers>, payload: &Value) -> HttpResult<String> {
+    fn delete(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
         let request = ureq::delete(url);
-        let sender = |req: Request| req.send_json(payload.clone());
-        self.request(request, headers, sender)
+        let sender = |req: Request| req.send_json(payload.clone()).map_err(Box::new);
+        self.request(request, headers, options, sender)
+    }
+
+    #[inline]
+    fn post_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
+        let request = ureq::post(url);
+        let sender = |req: Request| {
+            req.set("Content-Type", content_type)
+                .send_bytes(payload)
+                .map_err(Box::new)
+        };
+        self.request(request, headers, options, sender)
+    }
+
+    #[inline]
+    fn put_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+        options: &RequestOptions,
+    ) -> HttpResult<String> {
+        let request = ureq::put(url);
+        let sender = |req: Request| {
+            req.set("Content-Type", content_type)
+                .send_bytes(payload)
+                .map_err(Box::new)
+        };
+        self.request(request, headers, options, sender)
     }
 }
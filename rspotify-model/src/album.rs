@@ -10,7 +10,7 @@ use super::image::Image;
 use super::page::Page;
 use super::track::SimplifiedTrack;
 use super::Restriction;
-use crate::{AlbumType, Copyright, DatePrecision, Type};
+use crate::{AlbumId, AlbumType, Copyright, Country, DatePrecision, Type};
 
 /// Simplified Album Object
 ///
@@ -22,7 +22,7 @@ pub struct SimplifiedAlbum {
     pub album_type: Option<String>,
     pub artists: Vec<SimplifiedArtist>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub available_markets: Vec<String>,
+    pub available_markets: Vec<Country>,
     pub external_urls: HashMap<String, String>,
     pub href: Option<String>,
     pub id: Option<String>,
@@ -39,6 +39,13 @@ pub struct SimplifiedAlbum {
     pub uri: Option<String>,
 }
 
+impl SimplifiedAlbum {
+    /// The typed id of the album, or `None` if it's missing or invalid.
+    pub fn id(&self) -> Option<&AlbumId> {
+        self.id.as_deref().and_then(|id| AlbumId::from_id(id).ok())
+    }
+}
+
 /// Full Album Object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-albumobject)
@@ -46,7 +53,7 @@ pub struct SimplifiedAlbum {
 pub struct FullAlbum {
     pub artists: Vec<SimplifiedArtist>,
     pub album_type: AlbumType,
-    pub available_markets: Vec<String>,
+    pub available_markets: Vec<Country>,
     pub copyrights: Vec<Copyright>,
     pub external_ids: HashMap<String, String>,
     pub external_urls: HashMap<String, String>,
@@ -64,12 +71,21 @@ pub struct FullAlbum {
     pub uri: String,
 }
 
+impl FullAlbum {
+    /// The typed id of the album, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&AlbumId> {
+        AlbumId::from_id(&self.id).ok()
+    }
+}
+
 /// Full Albums wrapped by Vec object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-albums)
+///
+/// The albums that don't exist are `None`.
 #[derive(Deserialize)]
 pub struct FullAlbums {
-    pub albums: Vec<FullAlbum>,
+    pub albums: Vec<Option<FullAlbum>>,
 }
 
 /// Simplified Albums wrapped by Page object
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::image::Image;
 use super::page::CursorBasedPage;
-use crate::{Followers, Type};
+use crate::{ArtistId, Followers, Type};
 use std::collections::HashMap;
 
 /// Simplified Artist Object
@@ -21,6 +21,13 @@ pub struct SimplifiedArtist {
     pub uri: Option<String>,
 }
 
+impl SimplifiedArtist {
+    /// The typed id of the artist, or `None` if it's missing or invalid.
+    pub fn id(&self) -> Option<&ArtistId> {
+        self.id.as_deref().and_then(|id| ArtistId::from_id(id).ok())
+    }
+}
+
 /// Full Artist Object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-artistobject)
@@ -39,12 +46,21 @@ pub struct FullArtist {
     pub uri: String,
 }
 
+impl FullArtist {
+    /// The typed id of the artist, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&ArtistId> {
+        ArtistId::from_id(&self.id).ok()
+    }
+}
+
 /// Full artist object wrapped by `Vec`
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-artists)
+///
+/// The artists that don't exist are `None`.
 #[derive(Deserialize)]
 pub struct FullArtists {
-    pub artists: Vec<FullArtist>,
+    pub artists: Vec<Option<FullArtist>>,
 }
 
 /// Full Artists vector wrapped by cursor-based-page object
@@ -42,14 +42,23 @@ pub struct AudioFeaturesPayload {
 
 /// Audio analysis object
 ///
+/// Some of the fields are omitted by Spotify for certain tracks, so they're
+/// defaulted when missing.
+///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AudioAnalysis {
+    #[serde(default)]
     pub bars: Vec<TimeInterval>,
+    #[serde(default)]
     pub beats: Vec<TimeInterval>,
+    #[serde(default)]
     pub meta: AudioAnalysisMeta,
+    #[serde(default)]
     pub sections: Vec<AudioAnalysisSection>,
+    #[serde(default)]
     pub segments: Vec<AudioAnalysisSegment>,
+    #[serde(default)]
     pub tatums: Vec<TimeInterval>,
     pub track: AudioAnalysisTrack,
 }
@@ -85,7 +94,8 @@ pub struct AudioAnalysisSection {
 /// Audio analysis meta object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct AudioAnalysisMeta {
     pub analyzer_version: String,
     pub platform: String,
@@ -116,10 +126,14 @@ pub struct AudioAnalysisSegment {
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-audio-analysis)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AudioAnalysisTrack {
+    #[serde(default)]
     pub num_samples: u32,
     pub duration: f32,
+    #[serde(default)]
     pub sample_md5: String,
+    #[serde(default)]
     pub offset_seconds: u32,
+    #[serde(default)]
     pub window_seconds: u32,
     pub analysis_sample_rate: i32,
     pub analysis_channels: u32,
@@ -130,17 +144,26 @@ pub struct AudioAnalysisTrack {
     pub tempo_confidence: f32,
     pub time_signature: i32,
     pub time_signature_confidence: f32,
-    pub key: u32,
+    /// The key of the track, or -1 if none was detected.
+    pub key: i32,
     pub key_confidence: f32,
     #[serde(with = "modality")]
     pub mode: Modality,
     pub mode_confidence: f32,
+    #[serde(default)]
     pub codestring: String,
+    #[serde(default)]
     pub code_version: f32,
+    #[serde(default)]
     pub echoprintstring: String,
+    #[serde(default)]
     pub echoprint_version: f32,
+    #[serde(default)]
     pub synchstring: String,
+    #[serde(default)]
     pub synch_version: f32,
+    #[serde(default)]
     pub rhythmstring: String,
+    #[serde(default)]
     pub rhythm_version: f32,
 }
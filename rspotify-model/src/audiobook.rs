@@ -0,0 +1,55 @@
+//! All objects related to audiobooks
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{AudiobookId, Copyright, Country, Image};
+
+/// Author object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-authorobject)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Author {
+    pub name: String,
+}
+
+/// Narrator object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-narratorobject)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Narrator {
+    pub name: String,
+}
+
+/// Simplified audiobook object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedaudiobookobject)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SimplifiedAudiobook {
+    pub authors: Vec<Author>,
+    pub available_markets: Vec<Country>,
+    pub copyrights: Vec<Copyright>,
+    pub description: String,
+    pub edition: Option<String>,
+    pub explicit: bool,
+    pub external_urls: HashMap<String, String>,
+    pub href: String,
+    pub id: String,
+    pub images: Vec<Image>,
+    pub languages: Vec<String>,
+    pub media_type: String,
+    pub name: String,
+    pub narrators: Vec<Narrator>,
+    pub publisher: String,
+    pub total_chapters: Option<u32>,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub uri: String,
+}
+
+impl SimplifiedAudiobook {
+    /// The typed id of the audiobook, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&AudiobookId> {
+        AudiobookId::from_id(&self.id).ok()
+    }
+}
@@ -42,3 +42,18 @@ fn test_devices() {
     let payload: DevicePayload = serde_json::from_str(&json_str).unwrap();
     assert_eq!(payload.devices[0]._type, DeviceType::Computer)
 }
+
+#[test]
+fn test_device_types() {
+    let device_type: DeviceType = serde_json::from_str(r#""TV""#).unwrap();
+    assert_eq!(device_type, DeviceType::Tv);
+    assert_eq!(serde_json::to_string(&device_type).unwrap(), r#""TV""#);
+
+    let device_type: DeviceType = serde_json::from_str(r#""Smartwatch""#).unwrap();
+    assert_eq!(device_type, DeviceType::Unknown("Smartwatch".to_owned()));
+    assert_eq!(device_type.as_ref(), "Smartwatch");
+    assert_eq!(
+        serde_json::to_string(&device_type).unwrap(),
+        r#""Smartwatch""#
+    );
+}
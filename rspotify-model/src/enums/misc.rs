@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{de::IntoDeserializer, Deserialize, Deserializer, Serialize, Serializer};
 use strum::AsRefStr;
 
 use super::Country;
@@ -24,7 +25,7 @@ pub enum DisallowKey {
     TransferringPlayback,
 }
 
-/// Time range: `long-term`, `medium-term`, `short-term`.
+/// Time range: `long_term`, `medium_term`, `short_term`.
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/personalization/get-users-top-artists-and-tracks/)
 #[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, AsRefStr)]
@@ -98,7 +99,10 @@ pub enum Modality {
 /// Limit the response to a particular market
 ///
 /// FromToken is the same thing as setting the market parameter to the user's country.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+///
+/// It's (de)serialized as the string used by Spotify, i.e., the country code
+/// or `from_token`.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Market {
     Country(Country),
     FromToken,
@@ -112,3 +116,33 @@ impl AsRef<str> for Market {
         }
     }
 }
+
+impl Serialize for Market {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for Market {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let market = String::deserialize(deserializer)?;
+        if market == "from_token" {
+            Ok(Market::FromToken)
+        } else {
+            Country::deserialize(market.into_deserializer()).map(Market::Country)
+        }
+    }
+}
+
+/// Time limits for the cursor-based endpoints, like the recently played
+/// tracks: only the items played before or after the given time are returned.
+///
+/// It's sent as a Unix timestamp in milliseconds, which is also the format of
+/// the `before` and `after` fields of a [`Cursor`](crate::Cursor).
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimeLimits {
+    Before(DateTime<Utc>),
+    After(DateTime<Utc>),
+}
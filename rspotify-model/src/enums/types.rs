@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use strum::{AsRefStr, Display, EnumString};
 
 /// Copyright type: `C` = the copyright, `P` = the sound recording (performance)
@@ -28,12 +28,17 @@ pub enum AlbumType {
     Compilation,
 }
 
-/// Type: `artist`, `album`, `track`, `playlist`, `show` or `episode`
+/// Type: `artist`, `album`, `track`, `playlist`, `show`, `episode` or
+/// `audiobook`
+///
+/// The types added by Spotify that aren't supported yet are deserialized as
+/// [`Type::Unknown`].
 #[derive(
     Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, Display, EnumString, AsRefStr,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
 pub enum Type {
     Artist,
     Album,
@@ -42,6 +47,9 @@ pub enum Type {
     User,
     Show,
     Episode,
+    Audiobook,
+    #[serde(other)]
+    Unknown,
 }
 
 /// Additional typs: `track`, `episode`
@@ -50,6 +58,7 @@ pub enum Type {
 #[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, AsRefStr)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
 pub enum AdditionalType {
     Track,
     Episode,
@@ -70,12 +79,14 @@ pub enum CurrentlyPlayingType {
     Unknown,
 }
 
-/// Type for search: `artist`, `album`, `track`, `playlist`, `show`, `episode`
+/// Type for search: `artist`, `album`, `track`, `playlist`, `show`, `episode`,
+/// `audiobook`
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
 #[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, AsRefStr)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
 pub enum SearchType {
     Artist,
     Album,
@@ -83,27 +94,63 @@ pub enum SearchType {
     Playlist,
     Show,
     Episode,
+    Audiobook,
 }
 
-/// The user's Spotify subscription level: `premium`, `free`
+/// The user's Spotify subscription level: `premium`, `free`, `open`
 ///
-/// (The subscription level "open" can be considered the same as "free".)
+/// It's (de)serialized as the string used by Spotify. The subscription level
+/// "open" can usually be considered the same as "free", and the ones that
+/// aren't known are kept in [`SubscriptionLevel::Unknown`].
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-current-users-profile)
-#[derive(Clone, Serialize, Deserialize, Copy, PartialEq, Eq, Debug, AsRefStr)]
-#[serde(rename_all = "snake_case")]
-#[strum(serialize_all = "snake_case")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum SubscriptionLevel {
     Premium,
-    #[serde(alias = "open")]
     Free,
+    Open,
+    Unknown(String),
+}
+
+impl AsRef<str> for SubscriptionLevel {
+    fn as_ref(&self) -> &str {
+        match self {
+            SubscriptionLevel::Premium => "premium",
+            SubscriptionLevel::Free => "free",
+            SubscriptionLevel::Open => "open",
+            SubscriptionLevel::Unknown(level) => level,
+        }
+    }
+}
+
+impl Serialize for SubscriptionLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for SubscriptionLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let level = String::deserialize(deserializer)?;
+        Ok(match level.as_str() {
+            "premium" => SubscriptionLevel::Premium,
+            "free" => SubscriptionLevel::Free,
+            "open" => SubscriptionLevel::Open,
+            _ => SubscriptionLevel::Unknown(level),
+        })
+    }
 }
 
-/// Device Type: `computer`, `smartphone`, `speaker`, `TV`
+/// Device Type: `Computer`, `Smartphone`, `Speaker`, `TV`...
+///
+/// It's (de)serialized as the string used by Spotify. Since new types may be
+/// added at any time, the ones that aren't known are kept in
+/// [`DeviceType::Unknown`], which also includes Spotify's own `Unknown`.
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-deviceobject)
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, AsRefStr)]
-#[strum(serialize_all = "snake_case")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum DeviceType {
     Computer,
     Tablet,
@@ -117,7 +164,54 @@ pub enum DeviceType {
     CastVideo,
     CastAudio,
     Automobile,
-    Unknown,
+    Unknown(String),
+}
+
+impl AsRef<str> for DeviceType {
+    fn as_ref(&self) -> &str {
+        match self {
+            DeviceType::Computer => "Computer",
+            DeviceType::Tablet => "Tablet",
+            DeviceType::Smartphone => "Smartphone",
+            DeviceType::Speaker => "Speaker",
+            DeviceType::Tv => "TV",
+            DeviceType::Avr => "AVR",
+            DeviceType::Stb => "STB",
+            DeviceType::AudioDongle => "AudioDongle",
+            DeviceType::GameConsole => "GameConsole",
+            DeviceType::CastVideo => "CastVideo",
+            DeviceType::CastAudio => "CastAudio",
+            DeviceType::Automobile => "Automobile",
+            DeviceType::Unknown(device_type) => device_type,
+        }
+    }
+}
+
+impl Serialize for DeviceType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for DeviceType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let device_type = String::deserialize(deserializer)?;
+        Ok(match device_type.as_str() {
+            "Computer" => DeviceType::Computer,
+            "Tablet" => DeviceType::Tablet,
+            "Smartphone" => DeviceType::Smartphone,
+            "Speaker" => DeviceType::Speaker,
+            "TV" => DeviceType::Tv,
+            "AVR" => DeviceType::Avr,
+            "STB" => DeviceType::Stb,
+            "AudioDongle" => DeviceType::AudioDongle,
+            "GameConsole" => DeviceType::GameConsole,
+            "CastVideo" => DeviceType::CastVideo,
+            "CastAudio" => DeviceType::CastAudio,
+            "Automobile" => DeviceType::Automobile,
+            _ => DeviceType::Unknown(device_type),
+        })
+    }
 }
 
 /// Recommendations seed type
@@ -1,20 +1,91 @@
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 /// Matches errors that are returned from the Spotfiy
 /// API as part of the JSON response object.
-#[derive(Debug, thiserror::Error, Deserialize)]
+#[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     /// See [Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-errorobject)
     #[error("{status}: {message}")]
-    #[serde(alias = "error")]
     Regular { status: u16, message: String },
 
     /// See [Play Error Object](https://developer.spotify.com/documentation/web-api/reference/#object-playererrorobject)
     #[error("{status} ({reason}): {message}")]
-    #[serde(alias = "error")]
     Player {
         status: u16,
         message: String,
         reason: String,
     },
+
+    /// Returned by the accounts service during the authorization process.
+    /// See [Authorization Guide](https://developer.spotify.com/documentation/general/guides/authorization-guide/)
+    #[error("{error}: {error_description}")]
+    Auth {
+        error: String,
+        error_description: String,
+    },
+}
+
+impl ApiError {
+    /// The HTTP status code included in the error, if any. Note that
+    /// authorization errors don't include it.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ApiError::Regular { status, .. } | ApiError::Player { status, .. } => Some(*status),
+            ApiError::Auth { .. } => None,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ApiError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct ErrorObject {
+            status: u16,
+            message: String,
+            reason: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawApiError {
+            Object {
+                error: ErrorObject,
+            },
+            Auth {
+                error: String,
+                #[serde(default)]
+                error_description: String,
+            },
+        }
+
+        Ok(match RawApiError::deserialize(deserializer)? {
+            RawApiError::Object {
+                error:
+                    ErrorObject {
+                        status,
+                        message,
+                        reason: Some(reason),
+                    },
+            } => ApiError::Player {
+                status,
+                message,
+                reason,
+            },
+            RawApiError::Object {
+                error: ErrorObject {
+                    status, message, ..
+                },
+            } => ApiError::Regular { status, message },
+            RawApiError::Auth {
+                error,
+                error_description,
+            } => ApiError::Auth {
+                error,
+                error_description,
+            },
+        })
+    }
 }
@@ -3,7 +3,6 @@ use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
 use std::marker::PhantomData;
 use std::ops::Deref;
-use strum::Display;
 use thiserror::Error;
 
 // This is a sealed trait pattern implementation, it stops external code from
@@ -17,6 +16,9 @@ mod private {
     pub trait Sealed {}
 }
 
+/// The length of the IDs of every type but users, which are base62 strings.
+const ID_LENGTH: usize = 22;
+
 pub trait IdType: private::Sealed {
     const TYPE: Type;
 }
@@ -36,7 +38,7 @@ macro_rules! sealed_types {
     }
 }
 
-sealed_types!(Artist, Album, Track, Playlist, User, Show, Episode);
+sealed_types!(Artist, Album, Track, Playlist, User, Show, Episode, Audiobook);
 
 impl PlayContextIdType for Artist {}
 impl PlayContextIdType for Album {}
@@ -52,6 +54,7 @@ pub type PlaylistId = Id<Playlist>;
 pub type UserId = Id<User>;
 pub type ShowId = Id<Show>;
 pub type EpisodeId = Id<Episode>;
+pub type AudiobookId = Id<Audiobook>;
 
 pub type ArtistIdBuf = IdBuf<Artist>;
 pub type AlbumIdBuf = IdBuf<Album>;
@@ -60,6 +63,7 @@ pub type PlaylistIdBuf = IdBuf<Playlist>;
 pub type UserIdBuf = IdBuf<User>;
 pub type ShowIdBuf = IdBuf<Show>;
 pub type EpisodeIdBuf = IdBuf<Episode>;
+pub type AudiobookIdBuf = IdBuf<Audiobook>;
 
 /// A Spotify object id of given [type](crate::enums::types::Type).
 ///
@@ -132,20 +136,27 @@ impl<T: IdType> IdBuf<T> {
     }
 }
 
-/// Spotify id or URI parsing error
+/// Spotify id, URI or URL parsing error
 ///
 /// See also [`Id`](crate::idtypes::Id) for details.
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Display, Error)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Error)]
 pub enum IdError {
-    /// Spotify URI prefix is not `spotify:` or `spotify/`
+    /// Spotify URI prefix is not `spotify:` or `spotify/`, or the URL isn't
+    /// from `open.spotify.com`
+    #[error("invalid prefix: not a Spotify URI or URL")]
     InvalidPrefix,
-    /// Spotify URI can't be split into type and id parts
+    /// Spotify URI or URL can't be split into type and id parts
     /// (e.g. it has invalid separator)
+    #[error("invalid format: can't be split into type and id")]
     InvalidFormat,
-    /// Spotify URI has invalid type name, or id has invalid type in a given
-    /// context (e.g. a method expects a track id, but artist id is provided)
+    /// Spotify URI or URL has invalid type name, or id has invalid type in a
+    /// given context (e.g. a method expects a track id, but artist id is
+    /// provided)
+    #[error("invalid type: not the expected kind of Spotify object")]
     InvalidType,
-    /// Spotify id is invalid (empty or contains non-alphanumeric characters)
+    /// Spotify id is invalid (empty, contains non-alphanumeric characters or
+    /// doesn't have the expected length)
+    #[error("invalid id: not a valid Spotify id")]
     InvalidId,
 }
 
@@ -210,22 +221,24 @@ impl<T: IdType> Id<T> {
         format!("https://open.spotify.com/{}/{}", T::TYPE, &self.id)
     }
 
-    /// Parse Spotify id or URI from string slice
+    /// Parse Spotify id, URI or URL from string slice
     ///
     /// Spotify URI must be in one of the following formats:
     /// `spotify:{type}:{id}` or `spotify/{type}/{id}`.
     /// Where `{type}` is one of `artist`, `album`, `track`, `playlist`,
-    /// `user`, `show`, or `episode`, and `{id}` is a non-empty
-    /// alphanumeric string.
+    /// `user`, `show`, or `episode`, and `{id}` is exactly 22 base62
+    /// characters, or a non-empty username for users.
     /// The URI must be of given `T`ype, otherwise `IdError::InvalidType`
     /// error is returned.
     ///
     /// Examples: `spotify:album:6IcGNaXFRf5Y1jc7QsE9O2`,
     /// `spotify/track/4y4VO05kYgUTo2bzbox1an`.
     ///
-    /// If input string is not a valid Spotify URI (it's not started with
-    /// `spotify:` or `spotify/`), it must be a valid Spotify object id,
-    /// i.e. a non-empty alphanumeric string.
+    /// URLs are parsed as in [`Self::from_url`].
+    ///
+    /// If input string is not a valid Spotify URI or URL, it must be a valid
+    /// Spotify object id, i.e. exactly 22 base62 characters, or a non-empty
+    /// username for users.
     ///
     /// # Errors:
     ///
@@ -233,26 +246,36 @@ impl<T: IdType> Id<T> {
     ///    is not equal to `T`,
     /// - `IdError::InvalidId` - either if `id_or_uri` is an URI with invalid id
     ///    part, or it's an invalid id (id is invalid if it contains
-    ///    non-alphanumeric characters),
+    ///    non-alphanumeric characters or has an invalid length),
     /// - `IdError::InvalidFormat` - if `id_or_uri` is an URI, and it can't be
     ///    split into type and id parts.
     pub fn from_id_or_uri<'a, 'b: 'a>(id_or_uri: &'b str) -> Result<&'a Id<T>, IdError> {
         match Id::<T>::from_uri(id_or_uri) {
-            Ok(id) => Ok(id),
-            Err(IdError::InvalidPrefix) => Id::<T>::from_id(id_or_uri),
-            Err(error) => Err(error),
+            Err(IdError::InvalidPrefix) => match Id::<T>::from_url(id_or_uri) {
+                Err(IdError::InvalidPrefix) => Id::<T>::from_id(id_or_uri),
+                result => result,
+            },
+            result => result,
         }
     }
 
     /// Parse Spotify id from string slice
     ///
-    /// A valid Spotify object id must be a non-empty alphanumeric string.
+    /// A valid Spotify object id must be a base62 string, i.e. made of
+    /// alphanumeric characters, which is 22 characters long. The only
+    /// exception are user ids, which are usernames of any non-zero length.
     ///
     /// # Errors:
     ///
-    /// - `IdError::InvalidId` - if `id` contains non-alphanumeric characters.
+    /// - `IdError::InvalidId` - if `id` contains non-alphanumeric characters
+    ///   or has an invalid length.
     pub fn from_id<'a, 'b: 'a>(id: &'b str) -> Result<&'a Id<T>, IdError> {
-        if id.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+        let valid_length = match T::TYPE {
+            Type::User => !id.is_empty(),
+            _ => id.len() == ID_LENGTH,
+        };
+
+        if valid_length && id.chars().all(|ch| ch.is_ascii_alphanumeric()) {
             // Safe, b/c Id is just a str with ZST type tag, and id is proved
             // to be a valid id at this point
             Ok(unsafe { &*(id as *const str as *const Id<T>) })
@@ -266,7 +289,8 @@ impl<T: IdType> Id<T> {
     /// Spotify URI must be in one of the following formats:
     /// `spotify:{type}:{id}` or `spotify/{type}/{id}`.
     /// Where `{type}` is one of `artist`, `album`, `track`, `playlist`, `user`,
-    /// `show`, or `episode`, and `{id}` is a non-empty alphanumeric string.
+    /// `show`, or `episode`, and `{id}` is exactly 22 base62 characters, or a
+    /// non-empty username for users.
     ///
     /// Examples: `spotify:album:6IcGNaXFRf5Y1jc7QsE9O2`,
     /// `spotify/track/4y4VO05kYgUTo2bzbox1an`.
@@ -301,4 +325,99 @@ impl<T: IdType> Id<T> {
             _ => Err(IdError::InvalidType),
         }
     }
+
+    /// Parse Spotify URL from string slice
+    ///
+    /// Spotify URL must be in the format
+    /// `https://open.spotify.com/{type}/{id}`, where the scheme is optional
+    /// and `{type}` and `{id}` are the same as in [`Self::from_uri`]. The
+    /// query parameters (like `?si=...`) and localized paths (like
+    /// `/intl-es/track/...`) that Spotify uses in shared links are ignored.
+    ///
+    /// Examples: `https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an`,
+    /// `open.spotify.com/album/6IcGNaXFRf5Y1jc7QsE9O2?si=bfwFTlq0TZuxPTqm`.
+    ///
+    /// # Errors:
+    ///
+    /// - `IdError::InvalidPrefix` - if `url` isn't from `open.spotify.com`,
+    /// - `IdError::InvalidType` - if type part of an `url` is not a valid
+    ///   Spotify type `T`,
+    /// - `IdError::InvalidId` - if id part of an `url` is not a valid id,
+    /// - `IdError::InvalidFormat` - if its path isn't made of a type and an
+    ///   id.
+    pub fn from_url<'a, 'b: 'a>(url: &'b str) -> Result<&'a Id<T>, IdError> {
+        let url = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .unwrap_or(url);
+        let path = url
+            .strip_prefix("open.spotify.com/")
+            .ok_or(IdError::InvalidPrefix)?;
+        let path = match path.find(&['?', '#'][..]) {
+            Some(end) => &path[..end],
+            None => path,
+        };
+
+        let mut parts = path.split('/').collect::<Vec<_>>();
+        if matches!(parts.first(), Some(part) if part.starts_with("intl-")) {
+            parts.remove(0);
+        }
+
+        match parts.as_slice() {
+            [tpe, id] => match tpe.parse::<Type>() {
+                Ok(tpe) if tpe == T::TYPE => Id::<T>::from_id(id),
+                _ => Err(IdError::InvalidType),
+            },
+            _ => Err(IdError::InvalidFormat),
+        }
+    }
+}
+
+/// The id of an item that can be played, either a track or an episode. This
+/// makes it possible to use both kinds in the same list, e.g. when starting
+/// the playback of some items.
+///
+/// It can be obtained from either kind of id with `into()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayableId<'a> {
+    Track(&'a TrackId),
+    Episode(&'a EpisodeId),
+}
+
+impl<'a> PlayableId<'a> {
+    /// Spotify object type
+    pub fn _type(&self) -> Type {
+        match self {
+            PlayableId::Track(id) => id._type(),
+            PlayableId::Episode(id) => id._type(),
+        }
+    }
+
+    /// Spotify object id (guaranteed to be a string of alphanumeric characters)
+    pub fn id(&self) -> &'a str {
+        match self {
+            PlayableId::Track(id) => id.id(),
+            PlayableId::Episode(id) => id.id(),
+        }
+    }
+
+    /// Spotify object URI in a well-known format: spotify:type:id
+    pub fn uri(&self) -> String {
+        match self {
+            PlayableId::Track(id) => id.uri(),
+            PlayableId::Episode(id) => id.uri(),
+        }
+    }
+}
+
+impl<'a> From<&'a TrackId> for PlayableId<'a> {
+    fn from(id: &'a TrackId) -> Self {
+        PlayableId::Track(id)
+    }
+}
+
+impl<'a> From<&'a EpisodeId> for PlayableId<'a> {
+    fn from(id: &'a EpisodeId) -> Self {
+        PlayableId::Episode(id)
+    }
 }
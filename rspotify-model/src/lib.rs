@@ -1,10 +1,16 @@
 //! All Spotify API endpoint response objects.
 //!
+//! This crate doesn't depend on any of the HTTP clients, so it may be used on
+//! its own to (de)serialize the Spotify objects, e.g. in a service that
+//! doesn't perform requests. When using the full client, it's re-exported as
+//! `rspotify::model`.
+//!
 //! [Reference](https://developer.spotify.com/documentation/web-api/reference/#objects-index)
 
 pub mod album;
 pub mod artist;
 pub mod audio;
+pub mod audiobook;
 pub mod category;
 pub mod context;
 pub mod device;
@@ -12,6 +18,7 @@ pub mod enums;
 pub mod error;
 pub mod idtypes;
 pub mod image;
+pub mod market;
 pub mod offset;
 pub mod page;
 pub mod playing;
@@ -217,31 +224,34 @@ pub struct Restriction {
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-followersobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Followers {
-    // This field will always set to null, as the Web API does not support it at the moment.
+    // The `href` field is always null, as the Web API does not support it at
+    // the moment. It's left out so that it's ignored when deserializing,
+    // whether it's null, missing, or eventually a string.
     // pub href: Option<String>,
     pub total: u32,
 }
 
-/// A full track object or a full episode object
+/// A full track object or a full episode object, distinguished by their
+/// `type` field.
 ///
 /// + [Reference to full track](https://developer.spotify.com/documentation/web-api/reference/#object-trackobject)
 /// + [Reference to full episode](https://developer.spotify.com/documentation/web-api/reference/#object-episodeobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(untagged)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum PlayableItem {
     Track(track::FullTrack),
     Episode(show::FullEpisode),
 }
 
 pub use idtypes::{
-    AlbumId, AlbumIdBuf, ArtistId, ArtistIdBuf, EpisodeId, EpisodeIdBuf, Id, IdBuf, IdError,
-    PlayableIdType, PlaylistId, PlaylistIdBuf, ShowId, ShowIdBuf, TrackId, TrackIdBuf, UserId,
-    UserIdBuf,
+    AlbumId, AlbumIdBuf, ArtistId, ArtistIdBuf, AudiobookId, AudiobookIdBuf, EpisodeId,
+    EpisodeIdBuf, Id, IdBuf, IdError, PlayableId, PlayableIdType, PlaylistId, PlaylistIdBuf,
+    ShowId, ShowIdBuf, TrackId, TrackIdBuf, UserId, UserIdBuf,
 };
 pub use {
-    album::*, artist::*, audio::*, category::*, context::*, device::*, enums::*, error::*,
-    image::*, offset::*, page::*, playing::*, playlist::*, recommend::*, search::*, show::*,
-    track::*, user::*,
+    album::*, artist::*, audio::*, audiobook::*, category::*, context::*, device::*, enums::*,
+    error::*, image::*, market::*, offset::*, page::*, playing::*, playlist::*, recommend::*,
+    search::*, show::*, track::*, user::*,
 };
 
 #[cfg(test)]
@@ -286,6 +296,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_id_validation() {
+        let id = "4iV5W9uYEdYUVa79Axb7Rh";
+        assert_eq!(Id::<idtypes::Track>::from_id(id).unwrap().id(), id);
+        assert_eq!(Id::<idtypes::Episode>::from_id(id).unwrap().id(), id);
+
+        // Too short, too long, empty and non-base62 ids
+        for id in &[
+            "4iV5W9uYEdYUVa79Axb7R",
+            "4iV5W9uYEdYUVa79Axb7Rhh",
+            "",
+            "4iV5W9uYEdYUVa79Axb7R-",
+        ] {
+            assert_eq!(Err(IdError::InvalidId), Id::<idtypes::Track>::from_id(id));
+        }
+
+        // User ids are usernames, so their length may vary
+        assert_eq!(
+            "spotify",
+            Id::<idtypes::User>::from_id("spotify").unwrap().id()
+        );
+        assert_eq!(Err(IdError::InvalidId), Id::<idtypes::User>::from_id(""));
+    }
+
+    #[test]
+    fn test_id_from_url() {
+        let id = "4y4VO05kYgUTo2bzbox1an";
+        for url in &[
+            "https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an",
+            "http://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an",
+            "open.spotify.com/track/4y4VO05kYgUTo2bzbox1an",
+            "https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an?si=bfwFTlq0TZuxPTqm",
+            "https://open.spotify.com/intl-es/track/4y4VO05kYgUTo2bzbox1an?si=1#top",
+        ] {
+            assert_eq!(id, Id::<idtypes::Track>::from_url(url).unwrap().id());
+            assert_eq!(id, Id::<idtypes::Track>::from_id_or_uri(url).unwrap().id());
+        }
+
+        // Mismatched types
+        let url = "https://open.spotify.com/album/4y4VO05kYgUTo2bzbox1an";
+        assert_eq!(
+            Err(IdError::InvalidType),
+            Id::<idtypes::Track>::from_url(url)
+        );
+
+        // Malformed URLs
+        let url = "https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an/extra";
+        assert_eq!(
+            Err(IdError::InvalidFormat),
+            Id::<idtypes::Track>::from_url(url)
+        );
+        let url = "https://open.spotify.com/track/not-an-id";
+        assert_eq!(Err(IdError::InvalidId), Id::<idtypes::Track>::from_url(url));
+        let url = "https://example.com/track/4y4VO05kYgUTo2bzbox1an";
+        assert_eq!(
+            Err(IdError::InvalidPrefix),
+            Id::<idtypes::Track>::from_url(url)
+        );
+
+        // URIs and bare ids aren't URLs
+        assert_eq!(
+            Err(IdError::InvalidPrefix),
+            Id::<idtypes::Track>::from_url("spotify:track:4y4VO05kYgUTo2bzbox1an")
+        );
+        assert_eq!(
+            Err(IdError::InvalidPrefix),
+            Id::<idtypes::Track>::from_url(id)
+        );
+        assert_eq!(id, Id::<idtypes::Track>::from_id_or_uri(id).unwrap().id());
+    }
+
+    #[test]
+    fn test_id_error_display() {
+        assert_eq!(
+            IdError::InvalidType.to_string(),
+            "invalid type: not the expected kind of Spotify object"
+        );
+    }
+
     #[test]
     fn test_get_uri() {
         let track_id1 = "spotify:track:4iV5W9uYEdYUVa79Axb7Rh";
@@ -295,4 +384,34 @@ mod tests {
         assert_eq!(track_id1, &id1.uri());
         assert_eq!("spotify:track:1301WleyT98MSxVHPZCA6M", &id2.uri());
     }
+
+    #[test]
+    fn test_id_round_trip() {
+        fn check<T: idtypes::IdType + PartialEq + std::fmt::Debug>(id: &str) {
+            let id = Id::<T>::from_id(id).unwrap();
+            assert_eq!(Id::<T>::from_uri(&id.uri()), Ok(id));
+            assert_eq!(Id::<T>::from_url(&id.url()), Ok(id));
+
+            let buf = id.to_owned();
+            assert_eq!(buf.uri(), id.uri());
+            assert_eq!(buf.url(), id.url());
+            assert_eq!(buf.uri().parse::<IdBuf<T>>(), Ok(buf));
+        }
+
+        check::<idtypes::Artist>("2WX2uTcsvV5OnS0inACecP");
+        check::<idtypes::Album>("6IcGNaXFRf5Y1jc7QsE9O2");
+        check::<idtypes::Track>("4y4VO05kYgUTo2bzbox1an");
+        check::<idtypes::Playlist>("59ZbFPES4DQwEjBpWHzrtC");
+        check::<idtypes::User>("spotify");
+        check::<idtypes::Show>("4rOoJ6Egrf8K2IrywzwOMk");
+        check::<idtypes::Episode>("512ojhOuo1ktJprKbVcKyQ");
+
+        let id = TrackId::from_id("4y4VO05kYgUTo2bzbox1an").unwrap();
+        assert_eq!(id.uri(), "spotify:track:4y4VO05kYgUTo2bzbox1an");
+        assert_eq!(
+            id.url(),
+            "https://open.spotify.com/track/4y4VO05kYgUTo2bzbox1an"
+        );
+        assert_eq!(PlayableId::from(id).uri(), id.uri());
+    }
 }
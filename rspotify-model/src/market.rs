@@ -0,0 +1,13 @@
+//! All objects related to markets
+
+use serde::Deserialize;
+
+use crate::Market;
+
+/// Markets wrapped by an object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-available-markets)
+#[derive(Deserialize)]
+pub struct Markets {
+    pub markets: Vec<Market>,
+}
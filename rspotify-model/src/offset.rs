@@ -1,6 +1,6 @@
 //! Offset object
 
-use crate::{Id, IdBuf, PlayableIdType};
+use crate::{Id, IdBuf, PlayableId, PlayableIdType};
 
 /// Offset object
 ///
@@ -20,3 +20,23 @@ impl<T: PlayableIdType> Offset<T> {
         Offset::Uri(uri.to_owned())
     }
 }
+
+/// Offset object for a list of tracks and episodes, which may point to either
+/// of them, unlike [`Offset`].
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/player/start-a-users-playback/)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayableOffset<'a> {
+    Position(u32),
+    Uri(PlayableId<'a>),
+}
+
+impl<'a> PlayableOffset<'a> {
+    pub fn for_position(position: u32) -> PlayableOffset<'a> {
+        PlayableOffset::Position(position)
+    }
+
+    pub fn for_uri(uri: impl Into<PlayableId<'a>>) -> PlayableOffset<'a> {
+        PlayableOffset::Uri(uri.into())
+    }
+}
@@ -16,6 +16,26 @@ pub struct Page<T> {
     pub total: u32,
 }
 
+impl<T: Clone> Page<T> {
+    /// Returns the items in the page that match the given predicate. For
+    /// example, this can be used to drop the explicit tracks before queuing
+    /// them:
+    ///
+    /// ```
+    /// # use rspotify_model::{Page, SimplifiedTrack};
+    /// fn clean_tracks(page: &Page<SimplifiedTrack>) -> Vec<SimplifiedTrack> {
+    ///     page.filter_items(|track| !track.explicit)
+    /// }
+    /// ```
+    pub fn filter_items<P: FnMut(&T) -> bool>(&self, mut predicate: P) -> Vec<T> {
+        self.items
+            .iter()
+            .filter(|item| predicate(item))
+            .cloned()
+            .collect()
+    }
+}
+
 /// Cursor-based paging object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-cursorpagingobject)
@@ -33,8 +53,13 @@ pub struct CursorBasedPage<T> {
 
 /// Cursor object
 ///
+/// The recently played tracks include both cursors, as Unix timestamps in
+/// milliseconds, which can be passed as [`TimeLimits`](crate::TimeLimits) to
+/// continue paging. Other endpoints only include `after`.
+///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-cursorobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Cursor {
     pub after: Option<String>,
+    pub before: Option<String>,
 }
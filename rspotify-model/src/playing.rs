@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use super::context::Context;
 use super::track::FullTrack;
+use super::PlayableItem;
 
 /// Playing history object
 ///
@@ -15,3 +16,12 @@ pub struct PlayHistory {
     pub played_at: DateTime<Utc>,
     pub context: Option<Context>,
 }
+
+/// The user's queue, including the item that's currently playing
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-queue)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CurrentUserQueue {
+    pub currently_playing: Option<PlayableItem>,
+    pub queue: Vec<PlayableItem>,
+}
@@ -7,7 +7,7 @@ use std::collections::HashMap;
 use super::image::Image;
 use super::page::Page;
 use super::user::PublicUser;
-use crate::{Followers, PlayableItem, Type};
+use crate::{Followers, PlayableItem, PlaylistId, Type};
 
 /// Playlist result object
 ///
@@ -46,13 +46,21 @@ pub struct SimplifiedPlaylist {
     pub uri: String,
 }
 
+impl SimplifiedPlaylist {
+    /// The typed id of the playlist, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&PlaylistId> {
+        PlaylistId::from_id(&self.id).ok()
+    }
+}
+
 /// Full playlist object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-playlistobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct FullPlaylist {
     pub collaborative: bool,
-    pub description: String,
+    /// `None` for the playlists created without a description.
+    pub description: Option<String>,
     pub external_urls: HashMap<String, String>,
     pub followers: Followers,
     pub href: String,
@@ -68,6 +76,13 @@ pub struct FullPlaylist {
     pub uri: String,
 }
 
+impl FullPlaylist {
+    /// The typed id of the playlist, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&PlaylistId> {
+        PlaylistId::from_id(&self.id).ok()
+    }
+}
+
 /// Playlist track object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-playlisttrackobject)
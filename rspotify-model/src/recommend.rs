@@ -1,8 +1,14 @@
 //! All objects related to recommendation
 
 use super::track::SimplifiedTrack;
-use crate::RecommendationsSeedType;
+use crate::{ArtistId, Modality, RecommendationsSeedType, TrackId};
 use serde::{Deserialize, Serialize};
+use std::{mem, time::Duration};
+use thiserror::Error;
+
+/// The maximum number of seeds, counting artists, genres and tracks, that the
+/// recommendations endpoint accepts.
+pub const RECOMMENDATIONS_MAX_SEEDS: usize = 5;
 
 /// Recommendations object
 ///
@@ -29,3 +35,257 @@ pub struct RecommendationsSeed {
     #[serde(rename = "type")]
     pub _type: RecommendationsSeedType,
 }
+
+/// Genre seeds wrapped by an object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recommendation-genres)
+#[derive(Deserialize)]
+pub struct GenreSeeds {
+    pub genres: Vec<String>,
+}
+
+/// A tunable track attribute used to filter and target the recommendations.
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recommendations)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecommendationsAttribute {
+    Acousticness(f32),
+    Danceability(f32),
+    Duration(Duration),
+    Energy(f32),
+    Instrumentalness(f32),
+    Key(u32),
+    Liveness(f32),
+    Loudness(f32),
+    Mode(Modality),
+    Popularity(u32),
+    Speechiness(f32),
+    Tempo(f32),
+    TimeSignature(u32),
+    Valence(f32),
+}
+
+impl RecommendationsAttribute {
+    /// The name of the attribute in the query, without its `min_`, `max_` or
+    /// `target_` prefix.
+    pub fn name(&self) -> &'static str {
+        use RecommendationsAttribute::*;
+
+        match self {
+            Acousticness(_) => "acousticness",
+            Danceability(_) => "danceability",
+            Duration(_) => "duration_ms",
+            Energy(_) => "energy",
+            Instrumentalness(_) => "instrumentalness",
+            Key(_) => "key",
+            Liveness(_) => "liveness",
+            Loudness(_) => "loudness",
+            Mode(_) => "mode",
+            Popularity(_) => "popularity",
+            Speechiness(_) => "speechiness",
+            Tempo(_) => "tempo",
+            TimeSignature(_) => "time_signature",
+            Valence(_) => "valence",
+        }
+    }
+
+    /// The value of the attribute, used to compare its bounds.
+    fn value(&self) -> f64 {
+        use RecommendationsAttribute::*;
+
+        match *self {
+            Acousticness(x) | Danceability(x) | Energy(x) | Instrumentalness(x) | Liveness(x)
+            | Loudness(x) | Speechiness(x) | Tempo(x) | Valence(x) => x.into(),
+            Key(x) | Popularity(x) | TimeSignature(x) => x.into(),
+            Duration(x) => x.as_millis() as f64,
+            Mode(x) => (x as i8).into(),
+        }
+    }
+
+    /// The value of the attribute as it's sent in the query.
+    fn value_string(&self) -> String {
+        use RecommendationsAttribute::*;
+
+        match *self {
+            Acousticness(x) | Danceability(x) | Energy(x) | Instrumentalness(x) | Liveness(x)
+            | Loudness(x) | Speechiness(x) | Tempo(x) | Valence(x) => x.to_string(),
+            Key(x) | Popularity(x) | TimeSignature(x) => x.to_string(),
+            Duration(x) => x.as_millis().to_string(),
+            Mode(x) => (x as i8).to_string(),
+        }
+    }
+}
+
+/// The seeds and tunable attributes of a recommendations request.
+///
+/// Only the attributes that have been set are sent. Setting the same bound of
+/// an attribute twice overwrites its previous value.
+///
+/// ```
+/// use rspotify_model::{
+///     ArtistId, RecommendationsAttribute as Attribute, RecommendationsAttributes,
+/// };
+///
+/// let artist = ArtistId::from_id("4NHQUGzhtTLFvgF5SZesLK").unwrap();
+/// let attributes = RecommendationsAttributes::new()
+///     .seed_artists(vec![artist])
+///     .seed_genres(vec!["rock"])
+///     .min(Attribute::Energy(0.4))
+///     .target(Attribute::Tempo(120.0));
+/// assert!(attributes.to_query().is_ok());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecommendationsAttributes<'a> {
+    seed_artists: Vec<&'a ArtistId>,
+    seed_genres: Vec<&'a str>,
+    seed_tracks: Vec<&'a TrackId>,
+    min: Vec<RecommendationsAttribute>,
+    max: Vec<RecommendationsAttribute>,
+    target: Vec<RecommendationsAttribute>,
+}
+
+impl<'a> RecommendationsAttributes<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds artists to the seeds.
+    pub fn seed_artists(mut self, ids: impl IntoIterator<Item = &'a ArtistId>) -> Self {
+        self.seed_artists.extend(ids);
+        self
+    }
+
+    /// Adds genres to the seeds, which must be one of those listed by
+    /// `BaseClient::available_genre_seeds`.
+    pub fn seed_genres(mut self, genres: impl IntoIterator<Item = &'a str>) -> Self {
+        self.seed_genres.extend(genres);
+        self
+    }
+
+    /// Adds tracks to the seeds.
+    pub fn seed_tracks(mut self, ids: impl IntoIterator<Item = &'a TrackId>) -> Self {
+        self.seed_tracks.extend(ids);
+        self
+    }
+
+    /// Sets the minimum value of an attribute, filtering out the tracks below
+    /// it.
+    pub fn min(mut self, attribute: RecommendationsAttribute) -> Self {
+        set_attribute(&mut self.min, attribute);
+        self
+    }
+
+    /// Sets the maximum value of an attribute, filtering out the tracks above
+    /// it.
+    pub fn max(mut self, attribute: RecommendationsAttribute) -> Self {
+        set_attribute(&mut self.max, attribute);
+        self
+    }
+
+    /// Sets the target value of an attribute. The tracks nearest to it are
+    /// preferred.
+    pub fn target(mut self, attribute: RecommendationsAttribute) -> Self {
+        set_attribute(&mut self.target, attribute);
+        self
+    }
+
+    /// Validates the request and returns its query parameters.
+    ///
+    /// It fails if there are no seeds or more than
+    /// [`RECOMMENDATIONS_MAX_SEEDS`] of them, or if the minimum, target and
+    /// maximum values of an attribute are out of order.
+    pub fn to_query(&self) -> Result<Vec<(String, String)>, RecommendationsError> {
+        let seeds = self.seed_artists.len() + self.seed_genres.len() + self.seed_tracks.len();
+        if seeds == 0 {
+            return Err(RecommendationsError::NoSeeds);
+        }
+        if seeds > RECOMMENDATIONS_MAX_SEEDS {
+            return Err(RecommendationsError::TooManySeeds(seeds));
+        }
+
+        for attribute in self.min.iter().chain(&self.target).chain(&self.max) {
+            let values = [&self.min, &self.target, &self.max]
+                .iter()
+                .filter_map(|bound| find_attribute(bound, attribute))
+                .map(|x| x.value())
+                .collect::<Vec<_>>();
+            if values.windows(2).any(|pair| pair[0] > pair[1]) {
+                return Err(RecommendationsError::InvalidRange(attribute.name()));
+            }
+        }
+
+        let mut query = Vec::new();
+        let seeds = [
+            (
+                "seed_artists",
+                self.seed_artists
+                    .iter()
+                    .map(|id| id.id())
+                    .collect::<Vec<_>>(),
+            ),
+            ("seed_genres", self.seed_genres.clone()),
+            (
+                "seed_tracks",
+                self.seed_tracks.iter().map(|id| id.id()).collect(),
+            ),
+        ];
+        for (name, seeds) in seeds.iter() {
+            if !seeds.is_empty() {
+                query.push((name.to_string(), seeds.join(",")));
+            }
+        }
+
+        let attributes = [
+            ("min", &self.min),
+            ("max", &self.max),
+            ("target", &self.target),
+        ];
+        for (prefix, attributes) in attributes.iter() {
+            for attribute in attributes.iter() {
+                let name = format!("{}_{}", prefix, attribute.name());
+                query.push((name, attribute.value_string()));
+            }
+        }
+
+        Ok(query)
+    }
+}
+
+/// Inserts the attribute, replacing the previous one of the same kind.
+fn set_attribute(attributes: &mut Vec<RecommendationsAttribute>, new: RecommendationsAttribute) {
+    match attributes
+        .iter_mut()
+        .find(|x| mem::discriminant(*x) == mem::discriminant(&new))
+    {
+        Some(attribute) => *attribute = new,
+        None => attributes.push(new),
+    }
+}
+
+/// Finds the attribute of the same kind as the given one.
+fn find_attribute<'a>(
+    attributes: &'a [RecommendationsAttribute],
+    kind: &RecommendationsAttribute,
+) -> Option<&'a RecommendationsAttribute> {
+    attributes
+        .iter()
+        .find(|x| mem::discriminant(*x) == mem::discriminant(kind))
+}
+
+/// Errors returned when validating a [`RecommendationsAttributes`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RecommendationsError {
+    #[error("at least one seed artist, genre or track is required")]
+    NoSeeds,
+
+    #[error(
+        "at most {} seeds are allowed, but {0} were given",
+        RECOMMENDATIONS_MAX_SEEDS
+    )]
+    TooManySeeds(usize),
+
+    /// The minimum, target and maximum values of the attribute aren't in
+    /// increasing order.
+    #[error("the minimum, target and maximum values of {0} are out of order")]
+    InvalidRange(&'static str),
+}
@@ -2,6 +2,7 @@
 
 use super::album::SimplifiedAlbum;
 use super::artist::FullArtist;
+use super::audiobook::SimplifiedAudiobook;
 use super::page::Page;
 use super::playlist::SimplifiedPlaylist;
 use super::show::{SimplifiedEpisode, SimplifiedShow};
@@ -54,6 +55,14 @@ pub struct SearchEpisodes {
     pub episodes: Page<SimplifiedEpisode>,
 }
 
+/// Search for audiobooks
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SearchAudiobooks {
+    pub audiobooks: Page<SimplifiedAudiobook>,
+}
+
 /// Search result of any kind
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
@@ -71,4 +80,21 @@ pub enum SearchResult {
     Shows(Page<SimplifiedShow>),
     #[serde(rename = "episodes")]
     Episodes(Page<SimplifiedEpisode>),
+    #[serde(rename = "audiobooks")]
+    Audiobooks(Page<SimplifiedAudiobook>),
+}
+
+/// Search result for multiple types at once, where only the pages of the
+/// requested types are present
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SearchMultipleResult {
+    pub playlists: Option<Page<SimplifiedPlaylist>>,
+    pub albums: Option<Page<SimplifiedAlbum>>,
+    pub artists: Option<Page<FullArtist>>,
+    pub tracks: Option<Page<FullTrack>>,
+    pub shows: Option<Page<SimplifiedShow>>,
+    pub episodes: Option<Page<SimplifiedEpisode>>,
+    pub audiobooks: Option<Page<SimplifiedAudiobook>>,
 }
@@ -1,6 +1,7 @@
 use super::image::Image;
 use super::page::Page;
-use crate::{duration_ms, CopyrightType, DatePrecision};
+use crate::{duration_ms, CopyrightType, Country, DatePrecision, EpisodeId, ShowId};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -20,7 +21,7 @@ pub struct Copyright {
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedshowobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SimplifiedShow {
-    pub available_markets: Vec<String>,
+    pub available_markets: Vec<Country>,
     pub copyrights: Vec<Copyright>,
     pub description: String,
     pub explicit: bool,
@@ -38,12 +39,21 @@ pub struct SimplifiedShow {
     pub uri: String,
 }
 
+impl SimplifiedShow {
+    /// The typed id of the show, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&ShowId> {
+        ShowId::from_id(&self.id).ok()
+    }
+}
+
 /// SimplifiedShows wrapped by `Vec`
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-shows)
+///
+/// The shows that aren't available are `None`.
 #[derive(Deserialize)]
 pub struct SeversalSimplifiedShows {
-    pub shows: Vec<SimplifiedShow>,
+    pub shows: Vec<Option<SimplifiedShow>>,
 }
 
 /// Saved show object
@@ -60,7 +70,7 @@ pub struct Show {
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-showobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct FullShow {
-    pub available_markets: Vec<String>,
+    pub available_markets: Vec<Country>,
     pub copyrights: Vec<Copyright>,
     pub description: String,
     pub explicit: bool,
@@ -79,6 +89,13 @@ pub struct FullShow {
     pub uri: String,
 }
 
+impl FullShow {
+    /// The typed id of the show, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&ShowId> {
+        ShowId::from_id(&self.id).ok()
+    }
+}
+
 /// Simplified episode object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-simplifiedepisodeobject)
@@ -103,12 +120,21 @@ pub struct SimplifiedEpisode {
     pub name: String,
     pub release_date: String,
     pub release_date_precision: DatePrecision,
+    /// Only present when the `user-read-playback-position` scope has been
+    /// granted.
     pub resume_point: Option<ResumePoint>,
     #[serde(rename = "type")]
     pub _type: String,
     pub uri: String,
 }
 
+impl SimplifiedEpisode {
+    /// The typed id of the episode, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&EpisodeId> {
+        EpisodeId::from_id(&self.id).ok()
+    }
+}
+
 /// Full episode object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-episodeobject)
@@ -132,19 +158,37 @@ pub struct FullEpisode {
     pub name: String,
     pub release_date: String,
     pub release_date_precision: DatePrecision,
+    /// Only present when the `user-read-playback-position` scope has been
+    /// granted.
     pub resume_point: Option<ResumePoint>,
     pub show: SimplifiedShow,
-    #[serde(rename = "type")]
-    pub _type: String,
     pub uri: String,
 }
 
+impl FullEpisode {
+    /// The typed id of the episode, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&EpisodeId> {
+        EpisodeId::from_id(&self.id).ok()
+    }
+}
+
+/// Saved episode object
+///
+/// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-savedepisodeobject)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SavedEpisode {
+    pub added_at: DateTime<Utc>,
+    pub episode: FullEpisode,
+}
+
 /// Episodes feature object wrapped by `Vec`
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-episodes)
+///
+/// The episodes that aren't available are `None`.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct EpisodesPayload {
-    pub episodes: Vec<FullEpisode>,
+    pub episodes: Vec<Option<FullEpisode>>,
 }
 
 /// Resume point object
@@ -8,7 +8,7 @@ use std::{collections::HashMap, time::Duration};
 use super::album::SimplifiedAlbum;
 use super::artist::SimplifiedArtist;
 use super::Restriction;
-use crate::{duration_ms, TrackId, Type};
+use crate::{duration_ms, Country, TrackId, Type};
 
 /// Full track object
 ///
@@ -18,7 +18,7 @@ pub struct FullTrack {
     pub album: SimplifiedAlbum,
     pub artists: Vec<SimplifiedArtist>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
-    pub available_markets: Vec<String>,
+    pub available_markets: Vec<Country>,
     pub disc_number: i32,
     #[serde(with = "duration_ms", rename = "duration_ms")]
     pub duration: Duration,
@@ -38,11 +38,17 @@ pub struct FullTrack {
     pub popularity: u32,
     pub preview_url: Option<String>,
     pub track_number: u32,
-    #[serde(rename = "type")]
-    pub _type: Type,
     pub uri: String,
 }
 
+impl FullTrack {
+    /// The typed id of the track, or `None` if it's missing, like with local
+    /// tracks, or if it's invalid.
+    pub fn id(&self) -> Option<&TrackId> {
+        self.id.as_deref().and_then(|id| TrackId::from_id(id).ok())
+    }
+}
+
 /// Track link object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-linkedtrackobject)
@@ -56,12 +62,21 @@ pub struct TrackLink {
     pub uri: String,
 }
 
+impl TrackLink {
+    /// The typed id of the linked track, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&TrackId> {
+        TrackId::from_id(&self.id).ok()
+    }
+}
+
 /// Full track wrapped by `Vec`
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-several-tracks)
+///
+/// The tracks that don't exist are `None`.
 #[derive(Deserialize)]
 pub struct FullTracks {
-    pub tracks: Vec<FullTrack>,
+    pub tracks: Vec<Option<FullTrack>>,
 }
 
 /// Simplified track object.
@@ -73,7 +88,7 @@ pub struct FullTracks {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SimplifiedTrack {
     pub artists: Vec<SimplifiedArtist>,
-    pub available_markets: Option<Vec<String>>,
+    pub available_markets: Option<Vec<Country>>,
     pub disc_number: i32,
     #[serde(with = "duration_ms", rename = "duration_ms")]
     pub duration: Duration,
@@ -94,6 +109,14 @@ pub struct SimplifiedTrack {
     pub uri: String,
 }
 
+impl SimplifiedTrack {
+    /// The typed id of the track, or `None` if it's missing, like with local
+    /// tracks, or if it's invalid.
+    pub fn id(&self) -> Option<&TrackId> {
+        self.id.as_deref().and_then(|id| TrackId::from_id(id).ok())
+    }
+}
+
 /// Saved track object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-savedtrackobject)
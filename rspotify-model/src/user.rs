@@ -5,9 +5,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 use super::image::Image;
-use crate::{Country, Followers, SubscriptionLevel, Type};
+use crate::{Country, Followers, SubscriptionLevel, Type, UserId};
 
-/// Public user object
+/// Public user object, with the information of any user that's visible to
+/// everyone. See [`PrivateUser`] for the current user's profile.
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-publicuserobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -24,26 +25,45 @@ pub struct PublicUser {
     pub uri: String,
 }
 
+impl PublicUser {
+    /// The typed id of the user, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&UserId> {
+        UserId::from_id(&self.id).ok()
+    }
+}
+
 /// Private user object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-privateuserobject)
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PrivateUser {
+    /// Only available with the `user-read-private` scope.
     pub country: Option<Country>,
     pub display_name: Option<String>,
+    /// Only available with the `user-read-email` scope, and not verified by
+    /// Spotify.
     pub email: Option<String>,
     pub external_urls: HashMap<String, String>,
+    /// Only available with the `user-read-private` scope.
     pub explicit_content: Option<ExplicitContent>,
     pub followers: Option<Followers>,
     pub href: String,
     pub id: String,
     pub images: Option<Vec<Image>>,
+    /// Only available with the `user-read-private` scope.
     pub product: Option<SubscriptionLevel>,
     #[serde(rename = "type")]
     pub _type: Type,
     pub uri: String,
 }
 
+impl PrivateUser {
+    /// The typed id of the user, or `None` if it's invalid.
+    pub fn id(&self) -> Option<&UserId> {
+        UserId::from_id(&self.id).ok()
+    }
+}
+
 /// Explicit content setting object
 ///
 /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#object-explicitcontentsettingsobject)
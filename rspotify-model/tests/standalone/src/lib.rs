@@ -0,0 +1,41 @@
+//! Uses a few of the model types without a Spotify client.
+
+use rspotify_model::FullArtist;
+
+/// Parses an artist as returned by the Spotify API.
+pub fn parse_artist(json: &str) -> serde_json::Result<FullArtist> {
+    serde_json::from_str(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rspotify_model::{Country, Market, TrackId};
+
+    #[test]
+    fn test_standalone_model() {
+        let json = r#"{
+            "external_urls": {},
+            "followers": {"href": null, "total": 42},
+            "genres": ["rock"],
+            "href": "https://api.spotify.com/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+            "id": "0OdUWJ0sBjDrqHygGUXeCF",
+            "images": [],
+            "name": "Band of Horses",
+            "popularity": 59,
+            "type": "artist",
+            "uri": "spotify:artist:0OdUWJ0sBjDrqHygGUXeCF"
+        }"#;
+        let artist = parse_artist(json).unwrap();
+        assert_eq!(artist.name, "Band of Horses");
+        assert_eq!(artist.followers.total, 42);
+        assert_eq!(
+            serde_json::from_str::<FullArtist>(&serde_json::to_string(&artist).unwrap()).unwrap(),
+            artist
+        );
+
+        let track = TrackId::from_uri("spotify:track:4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+        assert_eq!(track.id(), "4iV5W9uYEdYUVa79Axb7Rh");
+        assert_eq!(Market::Country(Country::Spain).as_ref(), "ES");
+    }
+}
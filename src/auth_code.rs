@@ -1,5 +1,4 @@
 use crate::{
-    auth_urls,
     clients::{BaseClient, OAuthClient},
     headers,
     http::{Form, HttpClient},
@@ -29,7 +28,7 @@ use url::Url;
 ///    given redirect URI, including a code in the URL parameters. This happens
 ///    on your side.
 /// 2. The code obtained in the previous step is parsed with
-///    [`Self::parse_response_code`].
+///    [`Self::parse_response_code_and_verify_state`].
 /// 3. The code is sent to Spotify in order to obtain an access token with
 ///    [`Self::request_token`].
 /// 4. Finally, this access token can be used internally for the requests.
@@ -80,8 +79,8 @@ impl BaseClient for AuthCodeSpotify {
         self.token.as_ref()
     }
 
-    fn get_token_mut(&mut self) -> Option<&mut Token> {
-        self.token.as_mut()
+    fn get_token_mut(&mut self) -> &mut Option<Token> {
+        &mut self.token
     }
 
     fn get_creds(&self) -> &Credentials {
@@ -118,7 +117,8 @@ impl OAuthClient for AuthCodeSpotify {
         data.insert(headers::SCOPE, scopes.as_ref());
         data.insert(headers::STATE, oauth.state.as_ref());
 
-        let token = self.fetch_access_token(&data).await?;
+        let headers = self.creds.auth_headers();
+        let token = self.fetch_access_token(&data, Some(&headers)).await?;
         self.token = Some(token);
 
         self.write_token_cache()
@@ -132,8 +132,16 @@ impl OAuthClient for AuthCodeSpotify {
         data.insert(headers::REFRESH_TOKEN, refresh_token);
         data.insert(headers::GRANT_TYPE, headers::GRANT_REFRESH_TOKEN);
 
-        let mut token = self.fetch_access_token(&data).await?;
-        token.refresh_token = Some(refresh_token.to_string());
+        let headers = self.creds.auth_headers();
+        let mut token = self.fetch_access_token(&data, Some(&headers)).await?;
+        // Spotify may issue a new refresh token, which replaces the previous
+        // one. Otherwise, the previous one can still be used.
+        token
+            .refresh_token
+            .get_or_insert_with(|| refresh_token.to_string());
+        if let Some(hook) = &self.config.on_token_refresh {
+            hook.call(&token);
+        }
         self.token = Some(token);
 
         self.write_token_cache()
@@ -161,6 +169,20 @@ impl AuthCodeSpotify {
         }
     }
 
+    /// Builds a new [`AuthCodeSpotify`] from a previously obtained refresh
+    /// token, which is used right away to request an access token. This is
+    /// useful when the refresh token is stored elsewhere, like in a database,
+    /// so that the user doesn't have to authenticate again.
+    ///
+    /// Since the OAuth information isn't needed to refresh the token, it's
+    /// left with its default value.
+    #[maybe_async]
+    pub async fn from_refresh_token(creds: Credentials, refresh_token: &str) -> ClientResult<Self> {
+        let mut spotify = AuthCodeSpotify::new(creds, OAuth::default());
+        spotify.refresh_token(refresh_token).await?;
+        Ok(spotify)
+    }
+
     /// Same as [`Self::new`] but with an extra parameter to configure the
     /// client.
     pub fn with_config(creds: Credentials, oauth: OAuth, config: Config) -> Self {
@@ -172,6 +194,44 @@ impl AuthCodeSpotify {
         }
     }
 
+    /// Same as [`Self::from_token`] but with the credentials, OAuth
+    /// information and configuration of the client, so that a fully
+    /// configured client can be rebuilt from a stored token.
+    pub fn from_token_with_config(
+        token: Token,
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+    ) -> Self {
+        AuthCodeSpotify {
+            token: Some(token),
+            creds,
+            oauth,
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Same as [`Self::with_config`] but with an already built
+    /// `reqwest::Client`, so that its connection pool, timeouts and default
+    /// headers can be shared with the rest of your code. Note that the TLS
+    /// and proxy configuration will then come entirely from the given client.
+    #[cfg(feature = "client-reqwest")]
+    pub fn with_http_client(
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+        client: reqwest::Client,
+    ) -> Self {
+        AuthCodeSpotify {
+            creds,
+            oauth,
+            config,
+            http: client.into(),
+            ..Default::default()
+        }
+    }
+
     /// Returns the URL needed to authorize the current client as the first step
     /// in the authorization flow.
     pub fn get_authorize_url(&self, show_dialog: bool) -> ClientResult<String> {
@@ -193,7 +253,7 @@ impl AuthCodeSpotify {
             payload.insert(headers::SHOW_DIALOG, "true");
         }
 
-        let parsed = Url::parse_with_params(auth_urls::AUTHORIZE, payload)?;
+        let parsed = Url::parse_with_params(&self.config.authorize_url(), payload)?;
         Ok(parsed.into())
     }
 }
@@ -1,16 +1,22 @@
 use crate::{
-    auth_urls,
     clients::{BaseClient, OAuthClient},
-    headers,
+    generate_random_string, headers,
     http::{Form, HttpClient},
-    ClientResult, Config, Credentials, OAuth, Token,
+    ClientError, ClientResult, Config, Credentials, OAuth, Token,
 };
 
 use std::collections::HashMap;
 
 use maybe_async::maybe_async;
+use sha2::{Digest, Sha256};
 use url::Url;
 
+/// The length of the generated code verifier, which must be between 43 and 128
+/// characters long according to [RFC 7636][reference].
+///
+/// [reference]: https://tools.ietf.org/html/rfc7636#section-4.1
+const VERIFIER_LENGTH: usize = 128;
+
 /// The [Authorization Code Flow with Proof Key for Code Exchange
 /// (PKCE)][reference] client for the Spotify API.
 ///
@@ -30,6 +36,9 @@ pub struct AuthCodePkceSpotify {
     pub oauth: OAuth,
     pub config: Config,
     pub token: Option<Token>,
+    /// The code verifier for the authentication process. It's generated by
+    /// [`Self::get_authorize_url`] and required by [`Self::request_token`].
+    pub verifier: Option<String>,
     pub(in crate) http: HttpClient,
 }
 
@@ -43,8 +52,8 @@ impl BaseClient for AuthCodePkceSpotify {
         self.token.as_ref()
     }
 
-    fn get_token_mut(&mut self) -> Option<&mut Token> {
-        self.token.as_mut()
+    fn get_token_mut(&mut self) -> &mut Option<Token> {
+        &mut self.token
     }
 
     fn get_creds(&self) -> &Credentials {
@@ -64,36 +73,43 @@ impl OAuthClient for AuthCodePkceSpotify {
         &self.oauth
     }
 
+    /// Obtains a user access token given a code, as part of the OAuth
+    /// authentication. The access token will be saved internally.
+    ///
+    /// The code verifier generated by [`Self::get_authorize_url`] is sent
+    /// along with the code, so that method must have been called before.
     async fn request_token(&mut self, code: &str) -> ClientResult<()> {
-        // TODO
-        let mut data = Form::new();
-        let oauth = self.get_oauth();
-        let scopes = oauth
-            .scopes
-            .clone()
-            .into_iter()
-            .collect::<Vec<_>>()
-            .join(" ");
-        data.insert(headers::GRANT_TYPE, headers::GRANT_AUTH_CODE);
-        data.insert(headers::REDIRECT_URI, oauth.redirect_uri.as_ref());
-        data.insert(headers::CODE, code);
-        data.insert(headers::SCOPE, scopes.as_ref());
-        data.insert(headers::STATE, oauth.state.as_ref());
+        let verifier = self.verifier.clone().ok_or_else(|| {
+            ClientError::InvalidAuth(
+                "no code verifier found, make sure `get_authorize_url` is called \
+                 before requesting the token"
+                    .to_string(),
+            )
+        })?;
 
-        let token = self.fetch_access_token(&data).await?;
-        self.token = Some(token);
-
-        self.write_token_cache()
+        self.request_token_with_verifier(code, &verifier).await?;
+        // The verifier is only valid for a single token request
+        self.verifier = None;
+        Ok(())
     }
 
     async fn refresh_token(&mut self, refresh_token: &str) -> ClientResult<()> {
-        // TODO
         let mut data = Form::new();
         data.insert(headers::REFRESH_TOKEN, refresh_token);
         data.insert(headers::GRANT_TYPE, headers::GRANT_REFRESH_TOKEN);
+        // There's no secret in PKCE, so the client ID is sent in the body
+        // instead of the basic authorization.
+        data.insert(headers::CLIENT_ID, &self.creds.id);
 
-        let mut token = self.fetch_access_token(&data).await?;
-        token.refresh_token = Some(refresh_token.to_string());
+        let mut token = self.fetch_access_token(&data, None).await?;
+        // Spotify may issue a new refresh token, which replaces the previous
+        // one. Otherwise, the previous one can still be used.
+        token
+            .refresh_token
+            .get_or_insert_with(|| refresh_token.to_string());
+        if let Some(hook) = &self.config.on_token_refresh {
+            hook.call(&token);
+        }
         self.token = Some(token);
 
         self.write_token_cache()
@@ -132,10 +148,70 @@ impl AuthCodePkceSpotify {
         }
     }
 
+    /// Same as [`Self::from_token`] but with the credentials, OAuth
+    /// information and configuration of the client, so that a fully
+    /// configured client can be rebuilt from a stored token.
+    pub fn from_token_with_config(
+        token: Token,
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+    ) -> Self {
+        AuthCodePkceSpotify {
+            token: Some(token),
+            creds,
+            oauth,
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Same as [`Self::with_config`] but with an already built
+    /// `reqwest::Client`, so that its connection pool, timeouts and default
+    /// headers can be shared with the rest of your code. Note that the TLS
+    /// and proxy configuration will then come entirely from the given client.
+    #[cfg(feature = "client-reqwest")]
+    pub fn with_http_client(
+        creds: Credentials,
+        oauth: OAuth,
+        config: Config,
+        client: reqwest::Client,
+    ) -> Self {
+        AuthCodePkceSpotify {
+            creds,
+            oauth,
+            config,
+            http: client.into(),
+            ..Default::default()
+        }
+    }
+
     /// Returns the URL needed to authorize the current client as the first step
     /// in the authorization flow.
-    pub fn get_authorize_url(&self) -> ClientResult<String> {
-        // TODO
+    ///
+    /// A new code verifier is generated every time this is called, and it's
+    /// kept in the client until it's sent to Spotify with
+    /// [`Self::request_token`]. The URL only includes its code challenge.
+    pub fn get_authorize_url(&mut self, show_dialog: bool) -> ClientResult<String> {
+        let (url, verifier) = self.get_authorize_url_with_verifier(show_dialog)?;
+        self.verifier = Some(verifier);
+        Ok(url)
+    }
+
+    /// Same as [`Self::get_authorize_url`], but the generated code verifier is
+    /// returned along with the URL instead of being kept in the client.
+    ///
+    /// This is useful when the token is requested in a different process
+    /// than the one that generated the URL, like in a web server. The
+    /// verifier may then be saved in the user's session until it's passed to
+    /// [`Self::request_token_with_verifier`].
+    pub fn get_authorize_url_with_verifier(
+        &self,
+        show_dialog: bool,
+    ) -> ClientResult<(String, String)> {
+        let verifier = generate_random_string(VERIFIER_LENGTH);
+        let challenge = generate_code_challenge(&verifier);
+
         let mut payload: HashMap<&str, &str> = HashMap::new();
         let oauth = self.get_oauth();
         let scopes = oauth
@@ -149,10 +225,141 @@ impl AuthCodePkceSpotify {
         payload.insert(headers::REDIRECT_URI, &oauth.redirect_uri);
         payload.insert(headers::SCOPE, &scopes);
         payload.insert(headers::STATE, &oauth.state);
-        // payload.insert(headers::CODE_CHALLENGE, todo!());
-        // payload.insert(headers::CODE_CHALLENGE_METHOD, "S256");
+        payload.insert(headers::CODE_CHALLENGE, &challenge);
+        payload.insert(headers::CODE_CHALLENGE_METHOD, "S256");
+
+        if show_dialog {
+            payload.insert(headers::SHOW_DIALOG, "true");
+        }
+
+        let parsed = Url::parse_with_params(&self.config.authorize_url(), payload)?;
+        Ok((parsed.into(), verifier))
+    }
+
+    /// Obtains a user access token given a code and the code verifier
+    /// returned by [`Self::get_authorize_url_with_verifier`]. The access token
+    /// will be saved internally.
+    #[maybe_async]
+    pub async fn request_token_with_verifier(
+        &mut self,
+        code: &str,
+        verifier: &str,
+    ) -> ClientResult<()> {
+        let mut data = Form::new();
+        let oauth = self.get_oauth();
+        let scopes = oauth
+            .scopes
+            .clone()
+            .into_iter()
+            .collect::<Vec<_>>()
+            .join(" ");
+        data.insert(headers::GRANT_TYPE, headers::GRANT_AUTH_CODE);
+        data.insert(headers::REDIRECT_URI, oauth.redirect_uri.as_ref());
+        data.insert(headers::CODE, code);
+        data.insert(headers::SCOPE, scopes.as_ref());
+        data.insert(headers::STATE, oauth.state.as_ref());
+        data.insert(headers::CODE_VERIFIER, verifier);
+        data.insert(headers::CLIENT_ID, &self.creds.id);
+
+        let token = self.fetch_access_token(&data, None).await?;
+        self.token = Some(token);
+
+        self.write_token_cache()
+    }
+}
+
+/// Generates the code challenge for the given verifier, which is its SHA256
+/// hash encoded in base64url without padding.
+fn generate_code_challenge(verifier: &str) -> String {
+    let hash = Sha256::digest(verifier.as_bytes());
+    base64::encode_config(hash, base64::URL_SAFE_NO_PAD)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generate_code_challenge() {
+        // Test vector from RFC 7636, Appendix B
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        assert_eq!(
+            generate_code_challenge(verifier),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_get_authorize_url_verifier() {
+        let mut spotify = AuthCodePkceSpotify::default();
+        assert!(spotify.verifier.is_none());
+
+        let url = spotify.get_authorize_url(false).unwrap();
+        let verifier = spotify.verifier.clone().unwrap();
+        assert_eq!(verifier.len(), VERIFIER_LENGTH);
+
+        let challenge = generate_code_challenge(&verifier);
+        let params = Url::parse(&url)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<_, _>>();
+        assert_eq!(params.get(headers::CODE_CHALLENGE), Some(&challenge));
+        assert_eq!(
+            params
+                .get(headers::CODE_CHALLENGE_METHOD)
+                .map(String::as_str),
+            Some("S256")
+        );
+
+        // A new verifier is generated for every URL
+        spotify.get_authorize_url(false).unwrap();
+        assert_ne!(spotify.verifier.unwrap(), verifier);
+    }
+
+    #[test]
+    fn test_get_authorize_url_with_verifier() {
+        let spotify = AuthCodePkceSpotify::default();
+        let (url, verifier) = spotify.get_authorize_url_with_verifier(false).unwrap();
+        assert_eq!(verifier.len(), VERIFIER_LENGTH);
+        // The client itself isn't modified
+        assert!(spotify.verifier.is_none());
+
+        let params = Url::parse(&url)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect::<HashMap<_, _>>();
+        assert_eq!(
+            params.get(headers::CODE_CHALLENGE),
+            Some(&generate_code_challenge(&verifier))
+        );
+    }
+
+    #[test]
+    fn test_get_authorize_url_show_dialog() {
+        let mut spotify = AuthCodePkceSpotify::default();
+
+        let url = spotify.get_authorize_url(true).unwrap();
+        let url = Url::parse(&url).unwrap();
+        let params = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+        assert_eq!(
+            params.get(headers::SHOW_DIALOG).map(String::as_str),
+            Some("true")
+        );
+
+        let url = spotify.get_authorize_url(false).unwrap();
+        let url = Url::parse(&url).unwrap();
+        assert!(url
+            .query_pairs()
+            .all(|(key, _)| key != headers::SHOW_DIALOG));
+    }
 
-        let parsed = Url::parse_with_params(auth_urls::AUTHORIZE, payload)?;
-        Ok(parsed.into())
+    #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+    async fn test_request_token_without_verifier() {
+        let mut spotify = AuthCodePkceSpotify::default();
+        let err = spotify.request_token("code").await.unwrap_err();
+        assert!(matches!(err, ClientError::InvalidAuth(_)));
+        assert!(spotify.token.is_none());
     }
 }
@@ -0,0 +1,153 @@
+//! Token caching, which is configured with [`Config::cache_manager`].
+//!
+//! By default, tokens are saved as JSON into the file at
+//! [`Config::cache_path`] with [`FileCacheManager`], but a custom backend (a
+//! database, Redis...) may be used by implementing [`CacheManager`].
+//!
+//! There's no filesystem on WebAssembly, so a [`MemoryCacheManager`] is used
+//! by default instead, and [`FileCacheManager`] isn't available.
+//!
+//! [`Config::cache_manager`]: crate::Config::cache_manager
+//! [`Config::cache_path`]: crate::Config::cache_path
+
+use crate::{ClientResult, Token};
+
+use std::{fmt, sync::Mutex};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The interface for a token cache backend.
+///
+/// Note that the clients will only use it if [`Config::token_cached`] is
+/// enabled.
+///
+/// [`Config::token_cached`]: crate::Config::token_cached
+pub trait CacheManager: Send + Sync + fmt::Debug {
+    /// Reads the cached token, returning `None` in case there isn't any.
+    fn read(&self) -> ClientResult<Option<Token>>;
+
+    /// Saves the given token into the cache, overwriting the previous one.
+    fn write(&self, token: &Token) -> ClientResult<()>;
+
+    /// Removes the cached token. It shouldn't fail if there isn't any.
+    fn remove(&self) -> ClientResult<()>;
+}
+
+/// A cache manager that only keeps the token in memory, so it's lost when
+/// the manager is dropped. This is the default on WebAssembly.
+#[derive(Debug, Default)]
+pub struct MemoryCacheManager {
+    token: Mutex<Option<Token>>,
+}
+
+impl CacheManager for MemoryCacheManager {
+    fn read(&self) -> ClientResult<Option<Token>> {
+        Ok(self.token.lock().unwrap().clone())
+    }
+
+    fn write(&self, token: &Token) -> ClientResult<()> {
+        *self.token.lock().unwrap() = Some(token.clone());
+        Ok(())
+    }
+
+    fn remove(&self) -> ClientResult<()> {
+        *self.token.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+/// The default cache manager, which saves the token as JSON in a file.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FileCacheManager {
+    pub path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileCacheManager {
+    pub fn new<T: AsRef<Path>>(path: T) -> Self {
+        FileCacheManager {
+            path: path.as_ref().to_owned(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CacheManager for FileCacheManager {
+    fn read(&self) -> ClientResult<Option<Token>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(tok_str) => Ok(Some(serde_json::from_str(&tok_str)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write(&self, token: &Token) -> ClientResult<()> {
+        token.write_cache(&self.path)
+    }
+
+    fn remove(&self) -> ClientResult<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod test {
+    use super::*;
+    use crate::Config;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn token() -> Token {
+        Token {
+            access_token: "test-access-token".to_owned(),
+            ..Default::default()
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn test_memory_cache_manager() {
+        let manager = MemoryCacheManager::default();
+        assert!(manager.read().unwrap().is_none());
+
+        manager.write(&token()).unwrap();
+        assert_eq!(
+            manager.read().unwrap().unwrap().access_token,
+            "test-access-token"
+        );
+
+        manager.remove().unwrap();
+        assert!(manager.read().unwrap().is_none());
+        // Removing it again doesn't fail
+        manager.remove().unwrap();
+    }
+
+    /// Without a filesystem, the default cache manager is a new in-memory one
+    /// each time, so nothing is persisted.
+    #[wasm_bindgen_test]
+    fn test_default_cache_manager() {
+        let config = Config {
+            token_cached: true,
+            ..Default::default()
+        };
+        let manager = config.get_cache_manager();
+        manager.write(&token()).unwrap();
+        assert!(manager.read().unwrap().is_some());
+        assert!(config.get_cache_manager().read().unwrap().is_none());
+
+        // A custom one is used as is
+        let manager = std::sync::Arc::new(MemoryCacheManager::default());
+        let config = Config {
+            cache_manager: Some(manager.clone()),
+            ..config
+        };
+        config.get_cache_manager().write(&token()).unwrap();
+        assert!(manager.read().unwrap().is_some());
+    }
+}
@@ -37,8 +37,8 @@ impl BaseClient for ClientCredsSpotify {
         self.token.as_ref()
     }
 
-    fn get_token_mut(&mut self) -> Option<&mut Token> {
-        self.token.as_mut()
+    fn get_token_mut(&mut self) -> &mut Option<Token> {
+        &mut self.token
     }
 
     fn get_creds(&self) -> &Credentials {
@@ -80,6 +80,32 @@ impl ClientCredsSpotify {
         }
     }
 
+    /// Same as [`Self::from_token`] but with the credentials and
+    /// configuration of the client, so that a fully configured client can be
+    /// rebuilt from a stored token.
+    pub fn from_token_with_config(token: Token, creds: Credentials, config: Config) -> Self {
+        ClientCredsSpotify {
+            token: Some(token),
+            creds,
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Same as [`Self::with_config`] but with an already built
+    /// `reqwest::Client`, so that its connection pool, timeouts and default
+    /// headers can be shared with the rest of your code. Note that the TLS
+    /// and proxy configuration will then come entirely from the given client.
+    #[cfg(feature = "client-reqwest")]
+    pub fn with_http_client(creds: Credentials, config: Config, client: reqwest::Client) -> Self {
+        ClientCredsSpotify {
+            creds,
+            config,
+            http: client.into(),
+            ..Default::default()
+        }
+    }
+
     /// Tries to read the cache file's token, which may not exist.
     ///
     /// Similarly to [`Self::write_token_cache`], this will already check if the
@@ -90,8 +116,8 @@ impl ClientCredsSpotify {
             return None;
         }
 
-        let token = Token::from_cache(&self.get_config().cache_path)?;
-        if token.is_expired() {
+        let token = self.get_config().get_cache_manager().read().ok()??;
+        if token.is_expired_with_margin(self.get_config().token_expiry_margin) {
             // Invalid token, since it doesn't have at least the currently
             // required scopes or it's expired.
             None
@@ -107,7 +133,8 @@ impl ClientCredsSpotify {
         let mut data = Form::new();
         data.insert(headers::GRANT_TYPE, headers::GRANT_CLIENT_CREDS);
 
-        self.token = Some(self.fetch_access_token(&data).await?);
+        let headers = self.creds.auth_headers();
+        self.token = Some(self.fetch_access_token(&data, Some(&headers)).await?);
 
         self.write_token_cache()
     }
@@ -1,20 +1,27 @@
 use crate::{
-    auth_urls,
     clients::{
-        basic_auth, bearer_auth, convert_result, join_ids,
+        bearer_auth, convert_result, join_ids, log_response,
         pagination::{paginate, Paginator},
+        token_error, with_retries, LastResponse,
     },
     http::{BaseHttpClient, Form, Headers, HttpClient, Query},
     macros::build_map,
     model::*,
-    ClientResult, Config, Credentials, Token,
+    ClientError, ClientResult, Config, Credentials, Token,
 };
 
-use std::{collections::HashMap, fmt};
+use std::fmt;
 
 use chrono::Utc;
 use maybe_async::maybe_async;
-use serde_json::{Map, Value};
+use serde_json::Value;
+
+/// The maximum number of users whose follow of a playlist can be checked in
+/// a single request.
+const PLAYLIST_FOLLOWERS_LIMIT: usize = 5;
+
+/// The maximum number of shows or episodes that can be requested at once.
+const SHOWS_EPISODES_CHUNK: usize = 50;
 
 /// This trait implements the basic endpoints from the Spotify API that may be
 /// accessed without user authorization, including parts of the authentication
@@ -27,7 +34,7 @@ where
     fn get_config(&self) -> &Config;
     fn get_http(&self) -> &HttpClient;
     fn get_token(&self) -> Option<&Token>;
-    fn get_token_mut(&mut self) -> Option<&mut Token>;
+    fn get_token_mut(&mut self) -> &mut Option<Token>;
     fn get_creds(&self) -> &Credentials;
 
     /// If it's a relative URL like "me", the prefix is appended to it.
@@ -50,19 +57,28 @@ where
         Ok(auth)
     }
 
+    /// Whether the client currently has a usable token, i.e., one that exists
+    /// and hasn't expired, taking [`Config::token_expiry_margin`] into
+    /// account. Useful to decide whether the user has to log in again.
+    fn is_authenticated(&self) -> bool {
+        let margin = self.get_config().token_expiry_margin;
+        matches!(self.get_token(), Some(token) if !token.is_expired_with_margin(margin))
+    }
+
     // HTTP-related methods for the Spotify client. It wraps the basic HTTP
     // client with features needed of higher level.
     //
     // The Spotify client has two different wrappers to perform requests:
     //
-    // * Basic wrappers: `get`, `post`, `put`, `delete`, `post_form`. These only
-    //   append the configured Spotify API URL to the relative URL provided so
-    //   that it's not forgotten. They're used in the authentication process to
-    //   request an access token and similars.
+    // * Basic wrappers: `get`, `post`, `put`, `delete`, `post_form`,
+    //   `post_bytes`, `put_bytes`. These only append the configured Spotify API
+    //   URL to the relative URL provided so that it's not forgotten. They're
+    //   used in the authentication process to request an access token and
+    //   similars.
     // * Endpoint wrappers: `endpoint_get`, `endpoint_post`, `endpoint_put`,
-    //   `endpoint_delete`. These append the authentication headers for endpoint
-    //   requests to reduce the code needed for endpoints and make them as
-    //   concise as possible.
+    //   `endpoint_delete`, `endpoint_post_bytes`, `endpoint_put_bytes`. These
+    //   append the authentication headers for endpoint requests to reduce the
+    //   code needed for endpoints and make them as concise as possible.
 
     #[inline]
     async fn get(
@@ -72,7 +88,17 @@ where
         payload: &Query<'_>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().get(&url, headers, payload).await?)
+        if let Some(dry_run) = &self.get_config().dry_run {
+            return Ok(dry_run.record_query("GET", &url, headers, payload));
+        }
+        let last_response = LastResponse::default();
+        let options = self.get_config().request_options(&last_response);
+        let response = with_retries(self.get_config(), &last_response, true, || {
+            self.get_http().get(&url, headers, payload, &options)
+        })
+        .await?;
+        log_response(self.get_config(), &url, &response);
+        Ok(response)
     }
 
     #[inline]
@@ -83,7 +109,17 @@ where
         payload: &Value,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().post(&url, headers, payload).await?)
+        if let Some(dry_run) = &self.get_config().dry_run {
+            return Ok(dry_run.record_json("POST", &url, headers, payload));
+        }
+        let last_response = LastResponse::default();
+        let options = self.get_config().request_options(&last_response);
+        let response = with_retries(self.get_config(), &last_response, false, || {
+            self.get_http().post(&url, headers, payload, &options)
+        })
+        .await?;
+        log_response(self.get_config(), &url, &response);
+        Ok(response)
     }
 
     #[inline]
@@ -94,7 +130,17 @@ where
         payload: &Form<'_>,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().post_form(&url, headers, payload).await?)
+        if let Some(dry_run) = &self.get_config().dry_run {
+            return Ok(dry_run.record_form("POST", &url, headers, payload));
+        }
+        let last_response = LastResponse::default();
+        let options = self.get_config().request_options(&last_response);
+        let response = with_retries(self.get_config(), &last_response, false, || {
+            self.get_http().post_form(&url, headers, payload, &options)
+        })
+        .await?;
+        log_response(self.get_config(), &url, &response);
+        Ok(response)
     }
 
     #[inline]
@@ -105,7 +151,17 @@ where
         payload: &Value,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().put(&url, headers, payload).await?)
+        if let Some(dry_run) = &self.get_config().dry_run {
+            return Ok(dry_run.record_json("PUT", &url, headers, payload));
+        }
+        let last_response = LastResponse::default();
+        let options = self.get_config().request_options(&last_response);
+        let response = with_retries(self.get_config(), &last_response, false, || {
+            self.get_http().put(&url, headers, payload, &options)
+        })
+        .await?;
+        log_response(self.get_config(), &url, &response);
+        Ok(response)
     }
 
     #[inline]
@@ -116,7 +172,63 @@ where
         payload: &Value,
     ) -> ClientResult<String> {
         let url = self.endpoint_url(url);
-        Ok(self.get_http().delete(&url, headers, payload).await?)
+        if let Some(dry_run) = &self.get_config().dry_run {
+            return Ok(dry_run.record_json("DELETE", &url, headers, payload));
+        }
+        let last_response = LastResponse::default();
+        let options = self.get_config().request_options(&last_response);
+        let response = with_retries(self.get_config(), &last_response, false, || {
+            self.get_http().delete(&url, headers, payload, &options)
+        })
+        .await?;
+        log_response(self.get_config(), &url, &response);
+        Ok(response)
+    }
+
+    #[inline]
+    async fn post_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+    ) -> ClientResult<String> {
+        let url = self.endpoint_url(url);
+        if let Some(dry_run) = &self.get_config().dry_run {
+            return Ok(dry_run.record_bytes("POST", &url, headers, content_type, payload));
+        }
+        let last_response = LastResponse::default();
+        let options = self.get_config().request_options(&last_response);
+        let response = with_retries(self.get_config(), &last_response, false, || {
+            self.get_http()
+                .post_bytes(&url, headers, content_type, payload, &options)
+        })
+        .await?;
+        log_response(self.get_config(), &url, &response);
+        Ok(response)
+    }
+
+    #[inline]
+    async fn put_bytes(
+        &self,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+    ) -> ClientResult<String> {
+        let url = self.endpoint_url(url);
+        if let Some(dry_run) = &self.get_config().dry_run {
+            return Ok(dry_run.record_bytes("PUT", &url, headers, content_type, payload));
+        }
+        let last_response = LastResponse::default();
+        let options = self.get_config().request_options(&last_response);
+        let response = with_retries(self.get_config(), &last_response, false, || {
+            self.get_http()
+                .put_bytes(&url, headers, content_type, payload, &options)
+        })
+        .await?;
+        log_response(self.get_config(), &url, &response);
+        Ok(response)
     }
 
     /// The wrapper for the endpoints, which also includes the required
@@ -145,7 +257,51 @@ where
         self.delete(url, Some(&headers), payload).await
     }
 
-    /// Updates the cache file at the internal cache path.
+    #[inline]
+    async fn endpoint_post_bytes(
+        &self,
+        url: &str,
+        content_type: &str,
+        payload: &[u8],
+    ) -> ClientResult<String> {
+        let headers = self.auth_headers()?;
+        self.post_bytes(url, Some(&headers), content_type, payload)
+            .await
+    }
+
+    #[inline]
+    async fn endpoint_put_bytes(
+        &self,
+        url: &str,
+        content_type: &str,
+        payload: &[u8],
+    ) -> ClientResult<String> {
+        let headers = self.auth_headers()?;
+        self.put_bytes(url, Some(&headers), content_type, payload)
+            .await
+    }
+
+    /// Performs a GET request to any endpoint with the given query parameters,
+    /// returning the untyped JSON response. This is meant for endpoints or
+    /// parameters that Rspotify doesn't support yet, so prefer the typed
+    /// methods whenever possible.
+    ///
+    /// **This method is unstable**, and it may change or be removed in any
+    /// release.
+    ///
+    /// Parameters:
+    /// - endpoint - the endpoint relative to the API prefix, like
+    ///   `"me/top/artists"`, or an absolute URL
+    /// - params - the query parameters. If a key is repeated, only its last
+    ///   value is sent.
+    async fn get_raw(&self, endpoint: &str, params: &[(&str, &str)]) -> ClientResult<Value> {
+        let params = params.iter().copied().collect::<Query<'_>>();
+        let result = self.endpoint_get(endpoint, &params).await?;
+        convert_result(&result)
+    }
+
+    /// Updates the token cache with the configured cache manager, which is
+    /// the file at the internal cache path by default.
     ///
     /// This should be used whenever it's possible to, even if the cached token
     /// isn't configured, because this will already check `Config::token_cached`
@@ -156,24 +312,48 @@ where
         }
 
         if let Some(tok) = self.get_token().as_ref() {
-            tok.write_cache(&self.get_config().cache_path)?;
+            self.get_config().get_cache_manager().write(tok)?;
         }
 
         Ok(())
     }
 
-    /// Sends a request to Spotify for an access token.
-    async fn fetch_access_token(&self, payload: &Form<'_>) -> ClientResult<Token> {
-        // This request uses a specific content type, and the client ID/secret
-        // as the authentication, since the access token isn't available yet.
-        let mut head = Headers::new();
-        let (key, val) = basic_auth(&self.get_creds().id, &self.get_creds().secret);
-        head.insert(key, val);
+    /// Removes the token from the cache with the configured cache manager, and
+    /// the one in memory as well, which is useful to log out the user. Similarly
+    /// to [`Self::write_token_cache`], the cache is only accessed if
+    /// `Config::token_cached` is enabled.
+    ///
+    /// It's not an error if there wasn't any cached token.
+    fn remove_token_cache(&mut self) -> ClientResult<()> {
+        *self.get_token_mut() = None;
 
+        if self.get_config().token_cached {
+            self.get_config().get_cache_manager().remove()?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a request to Spotify for an access token.
+    ///
+    /// The errors returned by the accounts service, like an invalid client
+    /// secret or authorization code, are surfaced as
+    /// [`ClientError::InvalidAuth`] with their description.
+    ///
+    /// The access token isn't available yet, so each flow authenticates in its
+    /// own way: with the client ID and secret in `headers`, like
+    /// [`Credentials::auth_headers`](crate::Credentials::auth_headers), or
+    /// with the client ID in the payload for PKCE, which has no secret.
+    async fn fetch_access_token(
+        &self,
+        payload: &Form<'_>,
+        headers: Option<&Headers>,
+    ) -> ClientResult<Token> {
         let response = self
-            .post_form(auth_urls::TOKEN, Some(&head), payload)
-            .await?;
-        let mut tok = serde_json::from_str::<Token>(&response)?;
+            .post_form(&self.get_config().token_url, headers, payload)
+            .await
+            .map_err(token_error)?;
+        let mut tok = convert_result::<Token>(&response)?;
         tok.expires_at = Utc::now().checked_add_signed(tok.expires_in);
         Ok(tok)
     }
@@ -190,7 +370,35 @@ where
         convert_result(&result)
     }
 
+    /// Returns the full object of a simplified track, like the ones in an
+    /// album, using its id. Fails with [`ClientError::InvalidArgument`] if the
+    /// track doesn't have a valid id, as is the case for local tracks.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-track)
+    async fn track_from_simplified(&self, track: &SimplifiedTrack) -> ClientResult<FullTrack> {
+        let id = track.id().ok_or_else(|| {
+            ClientError::InvalidArgument(format!("the track '{}' doesn't have an id", track.name))
+        })?;
+        self.track(id).await
+    }
+
+    /// Returns the markets in which a track is available, given the track's
+    /// ID, URI or URL. There's no dedicated endpoint for this, so the full
+    /// track is requested.
+    ///
+    /// Parameters:
+    /// - track_id - a spotify URI, URL or ID
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-track)
+    async fn track_available_markets(&self, track_id: &TrackId) -> ClientResult<Vec<Country>> {
+        self.track(track_id)
+            .await
+            .map(|track| track.available_markets)
+    }
+
     /// Returns a list of tracks given a list of track IDs, URIs, or URLs.
+    /// They're in the same order as the IDs, with `None` for the ones that
+    /// don't exist.
     ///
     /// Parameters:
     /// - track_ids - a list of spotify URIs, URLs or IDs
@@ -201,10 +409,10 @@ where
         &self,
         track_ids: impl IntoIterator<Item = &'a TrackId> + 'a,
         market: Option<&Market>,
-    ) -> ClientResult<Vec<FullTrack>> {
+    ) -> ClientResult<Vec<Option<FullTrack>>> {
         let ids = join_ids(track_ids);
         let params = build_map! {
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
         };
 
         let url = format!("tracks/?ids={}", ids);
@@ -224,7 +432,9 @@ where
         convert_result(&result)
     }
 
-    /// Returns a list of artists given the artist IDs, URIs, or URLs.
+    /// Returns a list of artists given the artist IDs, URIs, or URLs. They're
+    /// in the same order as the IDs, with `None` for the ones that don't
+    /// exist.
     ///
     /// Parameters:
     /// - artist_ids - a list of artist IDs, URIs or URLs
@@ -233,7 +443,7 @@ where
     async fn artists<'a, Artists: IntoIterator<Item = &'a ArtistId>>(
         &self,
         artist_ids: Artists,
-    ) -> ClientResult<Vec<FullArtist>> {
+    ) -> ClientResult<Vec<Option<FullArtist>>> {
         let ids = join_ids(artist_ids);
         let url = format!("artists/?ids={}", ids);
         let result = self.endpoint_get(&url, &Query::new()).await?;
@@ -281,7 +491,7 @@ where
         let offset = offset.map(|x| x.to_string());
         let params = build_map! {
             optional "album_type": album_type.map(|x| x.as_ref()),
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
             optional "limit": limit.as_deref(),
             optional "offset": offset.as_deref(),
         };
@@ -291,12 +501,41 @@ where
         convert_result(&result)
     }
 
+    /// Get the first page of albums of multiple artists, performing the
+    /// requests concurrently. At most [`Config::max_concurrent_requests`] are
+    /// performed at the same time.
+    ///
+    /// The pages are returned in the same order as the given artists. If any
+    /// of the requests fails, the first error is returned instead.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-albums)
+    #[cfg(feature = "__async")]
+    async fn artists_albums(&self, ids: &[&ArtistId]) -> ClientResult<Vec<Page<SimplifiedAlbum>>> {
+        use futures::stream::{self, StreamExt, TryStreamExt};
+
+        stream::iter(ids)
+            .map(|id| self.artist_albums_manual(id, None, None, None, None))
+            .buffered(self.get_config().max_concurrent_requests.max(1))
+            .try_collect()
+            .await
+    }
+
     /// Get Spotify catalog information about an artist's top 10 tracks by
     /// country.
     ///
     /// Parameters:
     /// - artist_id - the artist ID, URI or URL
-    /// - market - limit the response to one particular country.
+    /// - market - limit the response to one particular country. It's required
+    ///   by Spotify, so unlike in other endpoints it can't be omitted:
+    ///
+    /// ```compile_fail
+    /// # use rspotify::{prelude::*, model::ArtistId, ClientCredsSpotify};
+    /// # #[maybe_async::maybe_async]
+    /// # async fn example(spotify: &ClientCredsSpotify) {
+    /// let artist = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+    /// let tracks = spotify.artist_top_tracks(artist, None).await;
+    /// # }
+    /// ```
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-an-artists-top-tracks)
     async fn artist_top_tracks(
@@ -310,7 +549,8 @@ where
 
         let url = format!("artists/{}/top-tracks", artist_id.id());
         let result = self.endpoint_get(&url, &params).await?;
-        convert_result::<FullTracks>(&result).map(|x| x.tracks)
+        // Unlike with `tracks`, there can't be any missing items here
+        convert_result::<FullTracks>(&result).map(|x| x.tracks.into_iter().flatten().collect())
     }
 
     /// Get Spotify catalog information about artists similar to an identified
@@ -324,7 +564,8 @@ where
     async fn artist_related_artists(&self, artist_id: &ArtistId) -> ClientResult<Vec<FullArtist>> {
         let url = format!("artists/{}/related-artists", artist_id.id());
         let result = self.endpoint_get(&url, &Query::new()).await?;
-        convert_result::<FullArtists>(&result).map(|x| x.artists)
+        // Unlike with `artists`, there can't be any missing items here
+        convert_result::<FullArtists>(&result).map(|x| x.artists.into_iter().flatten().collect())
     }
 
     /// Returns a single album given the album's ID, URIs or URL.
@@ -340,7 +581,9 @@ where
         convert_result(&result)
     }
 
-    /// Returns a list of albums given the album IDs, URIs, or URLs.
+    /// Returns a list of albums given the album IDs, URIs, or URLs. They're
+    /// in the same order as the IDs, with `None` for the ones that don't
+    /// exist.
     ///
     /// Parameters:
     /// - albums_ids - a list of album IDs, URIs or URLs
@@ -349,7 +592,7 @@ where
     async fn albums<'a, Albums: IntoIterator<Item = &'a AlbumId>>(
         &self,
         album_ids: Albums,
-    ) -> ClientResult<Vec<FullAlbum>> {
+    ) -> ClientResult<Vec<Option<FullAlbum>>> {
         let ids = join_ids(album_ids);
         let url = format!("albums/?ids={}", ids);
         let result = self.endpoint_get(&url, &Query::new()).await?;
@@ -364,7 +607,7 @@ where
     /// - limit  - the number of items to return
     /// - offset - the index of the first item to return
     /// - type - the type of item to return. One of 'artist', 'album', 'track',
-    ///  'playlist', 'show' or 'episode'
+    ///  'playlist', 'show', 'episode' or 'audiobook'
     /// - market - An ISO 3166-1 alpha-2 country code or the string from_token.
     /// - include_external: Optional.Possible values: audio. If
     ///   include_external=audio is specified the response will include any
@@ -385,7 +628,48 @@ where
         let params = build_map! {
             "q": q,
             "type": _type.as_ref(),
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
+            optional "include_external": include_external.map(|x| x.as_ref()),
+            optional "limit": limit.as_deref(),
+            optional "offset": offset.as_deref(),
+        };
+
+        let result = self.endpoint_get("search", &params).await?;
+        convert_result(&result)
+    }
+
+    /// Search for items of multiple types at once. Same as [`Self::search`],
+    /// but the result includes a page for each of the requested types.
+    ///
+    /// Parameters:
+    /// - q - the search query
+    /// - types - the types of item to return, e.g. shows and episodes
+    /// - market - An ISO 3166-1 alpha-2 country code or the string from_token.
+    /// - include_external: Optional.Possible values: audio.
+    /// - limit  - the number of items to return for each type
+    /// - offset - the index of the first item to return
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#category-search)
+    async fn search_multiple<'a>(
+        &self,
+        q: &str,
+        types: impl IntoIterator<Item = &'a SearchType> + 'a,
+        market: Option<&Market>,
+        include_external: Option<&IncludeExternal>,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<SearchMultipleResult> {
+        let types = types
+            .into_iter()
+            .map(|x| x.as_ref())
+            .collect::<Vec<_>>()
+            .join(",");
+        let limit = limit.map(|s| s.to_string());
+        let offset = offset.map(|s| s.to_string());
+        let params = build_map! {
+            "q": q,
+            "type": &types,
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
             optional "include_external": include_external.map(|x| x.as_ref()),
             optional "limit": limit.as_deref(),
             optional "offset": offset.as_deref(),
@@ -437,8 +721,12 @@ where
 
     /// Gets basic profile information about a Spotify User.
     ///
+    /// Only the public information is available, so the private details like
+    /// the email or the subscription level of the current user are obtained
+    /// with [`OAuthClient::me`](crate::clients::OAuthClient::me) instead.
+    ///
     /// Parameters:
-    /// - user - the id of the usr
+    /// - user - the id of the user
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-profile)
     async fn user(&self, user_id: &UserId) -> ClientResult<PublicUser> {
@@ -451,6 +739,10 @@ where
     ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
+    /// - fields - which fields to return, like `tracks.items(track(name,id))`.
+    ///   Note that the response will then fail to parse into a
+    ///   [`FullPlaylist`] unless all of its required fields are included, so
+    ///   it's best used with [`Self::get_raw`].
     /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-playlist)
@@ -462,7 +754,7 @@ where
     ) -> ClientResult<FullPlaylist> {
         let params = build_map! {
             optional "fields": fields,
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
         };
 
         let url = format!("playlists/{}", playlist_id.id());
@@ -498,10 +790,13 @@ where
 
     /// Check to see if the given users are following the given playlist.
     ///
+    /// The result has the same order as `user_ids`.
+    ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
-    /// - user_ids - the ids of the users that you want to
-    /// check to see if they follow the playlist. Maximum: 5 ids.
+    /// - user_ids - the ids of the users that you want to check to see if they
+    ///   follow the playlist. Spotify allows up to 5 users per request, so
+    ///   [`ClientError::InvalidArgument`] is returned if there are more.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-if-user-follows-playlist)
     async fn playlist_check_follow(
@@ -509,17 +804,21 @@ where
         playlist_id: &PlaylistId,
         user_ids: &[&UserId],
     ) -> ClientResult<Vec<bool>> {
-        if user_ids.len() > 5 {
-            log::error!("The maximum length of user ids is limited to 5 :-)");
+        if user_ids.len() > PLAYLIST_FOLLOWERS_LIMIT {
+            return Err(ClientError::InvalidArgument(format!(
+                "at most {} users can be checked, got {}",
+                PLAYLIST_FOLLOWERS_LIMIT,
+                user_ids.len()
+            )));
+        }
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
         }
+
         let url = format!(
             "playlists/{}/followers/contains?ids={}",
             playlist_id.id(),
-            user_ids
-                .iter()
-                .map(|id| id.id())
-                .collect::<Vec<_>>()
-                .join(","),
+            join_ids(user_ids.iter().copied()),
         );
         let result = self.endpoint_get(&url, &Query::new()).await?;
         convert_result(&result)
@@ -536,7 +835,7 @@ where
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-a-show)
     async fn get_a_show(&self, id: &ShowId, market: Option<&Market>) -> ClientResult<FullShow> {
         let params = build_map! {
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
         };
 
         let url = format!("shows/{}", id.id());
@@ -545,10 +844,14 @@ where
     }
 
     /// Get Spotify catalog information for multiple shows based on their
-    /// Spotify IDs.
+    /// Spotify IDs. The shows that aren't available are returned as `None`.
+    ///
+    /// Spotify accepts up to 50 IDs per request, so they're requested in
+    /// chunks of that size. If one of them fails, the error is returned as a
+    /// [`ClientError::Chunk`].
     ///
     /// Query Parameters
-    /// - ids(Required) A comma-separated list of the Spotify IDs for the shows. Maximum: 50 IDs.
+    /// - ids(Required) A comma-separated list of the Spotify IDs for the shows.
     /// - market(Optional) An ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-shows)
@@ -556,15 +859,30 @@ where
         &self,
         ids: impl IntoIterator<Item = &'a ShowId> + 'a,
         market: Option<&Market>,
-    ) -> ClientResult<Vec<SimplifiedShow>> {
-        let ids = join_ids(ids);
-        let params = build_map! {
-            "ids": &ids,
-            optional "market": market.map(|x| x.as_ref()),
-        };
+    ) -> ClientResult<Vec<Option<SimplifiedShow>>> {
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let market = self.get_config().market_or_default(market);
+
+        let mut shows = Vec::with_capacity(ids.len());
+        for (index, chunk) in ids.chunks(SHOWS_EPISODES_CHUNK).enumerate() {
+            let ids = join_ids(chunk.iter().copied());
+            let params = build_map! {
+                "ids": &ids,
+                optional "market": market.map(|x| x.as_ref()),
+            };
+
+            let result = self
+                .endpoint_get("shows", &params)
+                .await
+                .and_then(|result| convert_result::<SeversalSimplifiedShows>(&result))
+                .map_err(|err| ClientError::Chunk {
+                    index,
+                    source: Box::new(err),
+                })?;
+            shows.extend(result.shows);
+        }
 
-        let result = self.endpoint_get("shows", &params).await?;
-        convert_result::<SeversalSimplifiedShows>(&result).map(|x| x.shows)
+        Ok(shows)
     }
 
     /// Get Spotify catalog information about an show’s episodes. Optional
@@ -606,7 +924,7 @@ where
         let limit = limit.map(|x| x.to_string());
         let offset = offset.map(|x| x.to_string());
         let params = build_map! {
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
             optional "limit": limit.as_ref(),
             optional "offset": offset.as_ref(),
         };
@@ -632,7 +950,7 @@ where
     ) -> ClientResult<FullEpisode> {
         let url = format!("episodes/{}", id.id());
         let params = build_map! {
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
         };
 
         let result = self.endpoint_get(&url, &params).await?;
@@ -640,9 +958,13 @@ where
     }
 
     /// Get Spotify catalog information for multiple episodes based on their Spotify IDs.
+    /// The episodes that aren't available are returned as `None`.
+    ///
+    /// As with [`Self::get_several_shows`], the IDs are requested in chunks
+    /// of 50.
     ///
     /// Query Parameters
-    /// - ids: Required. A comma-separated list of the Spotify IDs for the episodes. Maximum: 50 IDs.
+    /// - ids: Required. A comma-separated list of the Spotify IDs for the episodes.
     /// - market: Optional. An ISO 3166-1 alpha-2 country code or the string from_token.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-multiple-episodes)
@@ -650,15 +972,30 @@ where
         &self,
         ids: impl IntoIterator<Item = &'a EpisodeId> + 'a,
         market: Option<&Market>,
-    ) -> ClientResult<Vec<FullEpisode>> {
-        let ids = join_ids(ids);
-        let params = build_map! {
-            "ids": &ids,
-            optional "market": market.map(|x| x.as_ref()),
-        };
+    ) -> ClientResult<Vec<Option<FullEpisode>>> {
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let market = self.get_config().market_or_default(market);
+
+        let mut episodes = Vec::with_capacity(ids.len());
+        for (index, chunk) in ids.chunks(SHOWS_EPISODES_CHUNK).enumerate() {
+            let ids = join_ids(chunk.iter().copied());
+            let params = build_map! {
+                "ids": &ids,
+                optional "market": market.map(|x| x.as_ref()),
+            };
+
+            let result = self
+                .endpoint_get("episodes", &params)
+                .await
+                .and_then(|result| convert_result::<EpisodesPayload>(&result))
+                .map_err(|err| ClientError::Chunk {
+                    index,
+                    source: Box::new(err),
+                })?;
+            episodes.extend(result.episodes);
+        }
 
-        let result = self.endpoint_get("episodes", &params).await?;
-        convert_result::<EpisodesPayload>(&result).map(|x| x.episodes)
+        Ok(episodes)
     }
 
     /// Get audio features for a track
@@ -827,7 +1164,7 @@ where
     ) -> ClientResult<FeaturedPlaylists> {
         let limit = limit.map(|x| x.to_string());
         let offset = offset.map(|x| x.to_string());
-        let timestamp = timestamp.map(|x| x.to_rfc3339());
+        let timestamp = timestamp.map(|x| x.format("%Y-%m-%dT%H:%M:%S").to_string());
         let params = build_map! {
             optional "locale": locale,
             optional "country": country.map(|x| x.as_ref()),
@@ -887,75 +1224,28 @@ where
     /// Get Recommendations Based on Seeds
     ///
     /// Parameters:
-    /// - seed_artists - a list of artist IDs, URIs or URLs
-    /// - seed_tracks - a list of artist IDs, URIs or URLs
-    /// - seed_genres - a list of genre names. Available genres for
+    /// - attributes - the seeds (artists, genres and tracks, up to 5 in
+    ///   total) and the tuneable track attributes to filter and target the
+    ///   results with. See [`RecommendationsAttributes`].
     /// - market - An ISO 3166-1 alpha-2 country code or the string from_token. If provided, all
     ///   results will be playable in this country.
     /// - limit - The maximum number of items to return. Default: 20.
     ///   Minimum: 1. Maximum: 100
-    /// - min/max/target_<attribute> - For the tuneable track attributes listed
-    ///   in the documentation, these values provide filters and targeting on
-    ///   results.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recommendations)
-    async fn recommendations<'a>(
+    async fn recommendations(
         &self,
-        payload: &Map<String, Value>,
-        seed_artists: Option<impl IntoIterator<Item = &'a ArtistId> + 'a>,
-        seed_genres: Option<impl IntoIterator<Item = &'a str> + 'a>,
-        seed_tracks: Option<impl IntoIterator<Item = &'a TrackId> + 'a>,
+        attributes: &RecommendationsAttributes<'_>,
         market: Option<&Market>,
         limit: Option<u32>,
     ) -> ClientResult<Recommendations> {
-        let seed_artists = seed_artists.map(join_ids);
-        let seed_genres = seed_genres.map(|x| x.into_iter().collect::<Vec<_>>().join(","));
-        let seed_tracks = seed_tracks.map(join_ids);
+        let query = attributes.to_query()?;
         let limit = limit.map(|x| x.to_string());
         let mut params = build_map! {
-            optional "seed_artists": seed_artists.as_ref(),
-            optional "seed_genres": seed_genres.as_ref(),
-            optional "seed_tracks": seed_tracks.as_ref(),
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
             optional "limit": limit.as_ref(),
         };
-
-        let attributes = [
-            "acousticness",
-            "danceability",
-            "duration_ms",
-            "energy",
-            "instrumentalness",
-            "key",
-            "liveness",
-            "loudness",
-            "mode",
-            "popularity",
-            "speechiness",
-            "tempo",
-            "time_signature",
-            "valence",
-        ];
-        let prefixes = ["min", "max", "target"];
-
-        // This map is used to store the intermediate data which lives long enough
-        // to be borrowed into the `params`
-        let map_to_hold_owned_value = attributes
-            .iter()
-            // create cartesian product for attributes and prefixes
-            .flat_map(|attribute| {
-                prefixes
-                    .iter()
-                    .map(move |prefix| format!("{}_{}", prefix, attribute))
-            })
-            .filter_map(
-                // TODO: not sure if this `to_string` is what we want. It
-                // might add quotes to the strings.
-                |param| payload.get(&param).map(|value| (param, value.to_string())),
-            )
-            .collect::<HashMap<_, _>>();
-
-        for (key, value) in &map_to_hold_owned_value {
+        for (key, value) in &query {
             params.insert(key, value);
         }
 
@@ -963,16 +1253,38 @@ where
         convert_result(&result)
     }
 
+    /// Get the list of genres that can be used as seeds for
+    /// [`Self::recommendations`].
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recommendation-genres)
+    async fn available_genre_seeds(&self) -> ClientResult<Vec<String>> {
+        let result = self
+            .endpoint_get("recommendations/available-genre-seeds", &Query::new())
+            .await?;
+        convert_result::<GenreSeeds>(&result).map(|x| x.genres)
+    }
+
+    /// Get the list of markets where Spotify is available.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-available-markets)
+    async fn available_markets(&self) -> ClientResult<Vec<Market>> {
+        let result = self.endpoint_get("markets", &Query::new()).await?;
+        convert_result::<Markets>(&result).map(|x| x.markets)
+    }
+
     /// Get full details of the tracks of a playlist owned by a user.
     ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
-    /// - fields - which fields to return
+    /// - fields - which fields to return, like `items(track(name,id))`. As
+    ///   with [`Self::playlist`], the items must include all the fields
+    ///   required by [`PlaylistItem`].
     /// - limit - the maximum number of tracks to return
     /// - offset - the index of the first track to return
     /// - market - an ISO 3166-1 alpha-2 country code or the string from_token.
     ///
-    /// See [`Self::playlist_tracks`] for a manually paginated version of this.
+    /// See [`Self::playlist_tracks_manual`] for a manually paginated version
+    /// of this.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-playlists-tracks)
     fn playlist_tracks<'a>(
@@ -1002,7 +1314,7 @@ where
         let offset = offset.map(|s| s.to_string());
         let params = build_map! {
             optional "fields": fields,
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
             optional "limit": limit.as_deref(),
             optional "offset": offset.as_deref(),
         };
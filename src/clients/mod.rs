@@ -6,21 +6,88 @@ pub use base::BaseClient;
 pub use oauth::OAuthClient;
 
 use crate::{
-    model::{idtypes::IdType, Id},
-    ClientResult, Token,
+    http::{Headers, HttpError, HttpResult, ResponseHook},
+    model::{idtypes::IdType, ApiError, Id},
+    ClientError, ClientResult, Config, Token,
+};
+
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[cfg(feature = "cli-server")]
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::TcpListener,
 };
 
 use serde::Deserialize;
+use serde_json::Value;
+use url::form_urlencoded;
+#[cfg(feature = "cli-server")]
+use url::Url;
+
+/// The maximum length of the response included in parse errors.
+const RESPONSE_SNIPPET_LEN: usize = 256;
+
+/// The fields of a response that are never logged nor included in errors.
+const REDACTED_FIELDS: [&str; 2] = ["access_token", "refresh_token"];
 
 /// Converts a JSON response from Spotify into its model.
 pub(in crate) fn convert_result<'a, T: Deserialize<'a>>(input: &'a str) -> ClientResult<T> {
-    serde_json::from_str::<T>(input).map_err(Into::into)
+    serde_json::from_str::<T>(input).map_err(|source| ClientError::ParseResponse {
+        source,
+        body: response_snippet(input),
+    })
+}
+
+/// Hides the tokens in a response body, so that it can be logged safely. If
+/// it can't be parsed but seems to include them, it's hidden completely.
+fn redact_tokens(body: &str) -> Cow<'_, str> {
+    if !REDACTED_FIELDS.iter().any(|field| body.contains(field)) {
+        return Cow::Borrowed(body);
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Object(mut map)) => {
+            for field in REDACTED_FIELDS.iter() {
+                if let Some(value) = map.get_mut(*field) {
+                    *value = Value::from("<redacted>");
+                }
+            }
+            Cow::Owned(Value::Object(map).to_string())
+        }
+        Ok(_) => Cow::Borrowed(body),
+        Err(_) => Cow::Borrowed("<redacted>"),
+    }
+}
+
+/// Returns the beginning of a response body with its tokens redacted, to be
+/// included in error messages.
+fn response_snippet(body: &str) -> String {
+    let body = redact_tokens(body);
+    match body.char_indices().nth(RESPONSE_SNIPPET_LEN) {
+        Some((end, _)) => format!("{}...", &body[..end]),
+        None => body.into_owned(),
+    }
+}
+
+/// Logs the body of a response if [`Config::log_raw_responses`] is enabled.
+pub(in crate) fn log_response(config: &Config, url: &str, body: &str) {
+    if config.log_raw_responses {
+        log::debug!("Response from {}: {}", url, redact_tokens(body));
+    }
 }
 
-/// Append device ID to an API path.
+/// Append device ID to an API path. It's percent-encoded, since it's included
+/// in the query string.
 pub(in crate) fn append_device_id(path: &str, device_id: Option<&str>) -> String {
     let mut new_path = path.to_string();
     if let Some(_device_id) = device_id {
+        let _device_id = form_urlencoded::byte_serialize(_device_id.as_bytes()).collect::<String>();
         if path.contains('?') {
             new_path.push_str(&format!("&device_id={}", _device_id));
         } else {
@@ -30,6 +97,255 @@ pub(in crate) fn append_device_id(path: &str, device_id: Option<&str>) -> String
     new_path
 }
 
+/// Returns how long to wait before retrying a request that failed with the
+/// given error, or `None` if it shouldn't be retried. `attempt` is the number
+/// of retries already performed, and `idempotent` is whether the request may
+/// be safely repeated after a connection error.
+fn retry_delay(
+    config: &Config,
+    err: &HttpError,
+    attempt: u32,
+    idempotent: bool,
+) -> Option<Duration> {
+    if attempt >= config.max_retries {
+        return None;
+    }
+
+    match err {
+        HttpError::RateLimited(retry_after) if config.retry_on_rate_limit => {
+            let secs = match retry_after {
+                Some(secs) => *secs as u64,
+                None => 2u64.saturating_pow(attempt),
+            };
+            Some(Duration::from_secs(secs))
+        }
+        HttpError::Connection(_) if config.retry_on_transient && idempotent => {
+            Some(Duration::from_secs(2u64.saturating_pow(attempt)))
+        }
+        _ => None,
+    }
+}
+
+/// The status code and headers of the last response received for a request.
+///
+/// The HTTP client reports every response, including the ones that are retried,
+/// so they're kept here until the request finishes. This way,
+/// [`Config::on_response`] is only invoked once, with the final response.
+#[derive(Clone, Debug, Default)]
+pub(in crate) struct LastResponse(Arc<Mutex<Option<(u16, Headers)>>>);
+
+impl LastResponse {
+    /// The hook to be passed to the HTTP client, which keeps the responses.
+    pub(in crate) fn hook(&self) -> ResponseHook {
+        let last = Arc::clone(&self.0);
+        ResponseHook::new(move |status, headers| {
+            *last.lock().unwrap() = Some((status, headers.clone()));
+        })
+    }
+
+    /// Forgets the previous response before a new attempt, in case the next
+    /// one fails without a response, e.g. with a connection error.
+    fn clear(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+
+    /// Invokes [`Config::on_response`] with the last response, if any.
+    fn notify(&self, config: &Config) {
+        let last = self.0.lock().unwrap().take();
+        if let (Some(hook), Some((status, headers))) = (&config.on_response, last) {
+            hook.call(status, &headers);
+        }
+    }
+}
+
+/// Performs the request repeatedly until it isn't rate limited anymore or the
+/// connection succeeds, as configured in [`Config::retry_on_rate_limit`] and
+/// [`Config::retry_on_transient`]. The request must use the options from
+/// [`Config::request_options`] with the same `last_response`.
+#[cfg(feature = "__async")]
+pub(in crate) async fn with_retries<F, Fut>(
+    config: &Config,
+    last_response: &LastResponse,
+    idempotent: bool,
+    request: F,
+) -> ClientResult<String>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = HttpResult<String>>,
+{
+    let mut attempt = 0;
+    let result = loop {
+        last_response.clear();
+        match request().await {
+            Err(err) => match retry_delay(config, &err, attempt, idempotent) {
+                Some(delay) => {
+                    log::warn!("Request failed ({}), retrying in {:?}", err, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => break Err(err.into()),
+            },
+            Ok(response) => break Ok(response),
+        }
+    };
+    last_response.notify(config);
+    result
+}
+
+/// Performs the request repeatedly until it isn't rate limited anymore or the
+/// connection succeeds, as configured in [`Config::retry_on_rate_limit`] and
+/// [`Config::retry_on_transient`]. The request must use the options from
+/// [`Config::request_options`] with the same `last_response`.
+#[cfg(feature = "__sync")]
+pub(in crate) fn with_retries<F>(
+    config: &Config,
+    last_response: &LastResponse,
+    idempotent: bool,
+    request: F,
+) -> ClientResult<String>
+where
+    F: Fn() -> HttpResult<String>,
+{
+    let mut attempt = 0;
+    let result = loop {
+        last_response.clear();
+        match request() {
+            Err(err) => match retry_delay(config, &err, attempt, idempotent) {
+                Some(delay) => {
+                    log::warn!("Request failed ({}), retrying in {:?}", err, delay);
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                None => break Err(err.into()),
+            },
+            Ok(response) => break Ok(response),
+        }
+    };
+    last_response.notify(config);
+    result
+}
+
+/// Surfaces the errors returned by the accounts service when requesting a
+/// token, like an invalid client secret or authorization code, as
+/// [`ClientError::InvalidAuth`] so that misconfigured credentials are obvious.
+pub(in crate) fn token_error(err: ClientError) -> ClientError {
+    match err {
+        ClientError::Api(ApiError::Auth {
+            error,
+            error_description,
+        }) => {
+            if error_description.is_empty() {
+                ClientError::InvalidAuth(error)
+            } else {
+                ClientError::InvalidAuth(format!("{}: {}", error, error_description))
+            }
+        }
+        err => err,
+    }
+}
+
+/// Makes sure that the token has been granted the scopes required by an
+/// endpoint before performing the request, so that a clearer error than
+/// Spotify's 403 is returned otherwise.
+///
+/// Tokens without any scopes are skipped, since they may have been built
+/// manually with unknown scopes.
+pub(in crate) fn require_scopes(
+    token: Option<&Token>,
+    required: &HashSet<String>,
+) -> ClientResult<()> {
+    let token = match token {
+        Some(token) if !token.scopes.is_empty() => token,
+        _ => return Ok(()),
+    };
+
+    let mut missing = token
+        .missing_scopes(required)
+        .into_iter()
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    missing.sort();
+    Err(ClientError::InvalidAuth(format!(
+        "the token is missing the required scopes: {}",
+        missing.join(", ")
+    )))
+}
+
+/// Binds a listener to the address of the redirect URI, which must be a
+/// local `http` one, like `http://localhost:8888/callback`.
+#[cfg(feature = "cli-server")]
+pub(in crate) fn redirect_listener(redirect_uri: &Url) -> io::Result<TcpListener> {
+    let is_local = matches!(
+        redirect_uri.host_str(),
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+    );
+    if redirect_uri.scheme() != "http" || !is_local {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the redirect URI isn't a local http one",
+        ));
+    }
+
+    TcpListener::bind(&*redirect_uri.socket_addrs(|| None)?)
+}
+
+/// Waits until the browser is redirected to the redirect URI, and returns the
+/// full URL it was redirected to. Requests to other paths, like the favicon,
+/// are ignored.
+#[cfg(feature = "cli-server")]
+pub(in crate) fn wait_for_redirect(
+    listener: &TcpListener,
+    redirect_uri: &Url,
+) -> ClientResult<String> {
+    const NOT_FOUND: &str =
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const DONE: &str =
+        "Authorization finished, you may close this window and go back to the terminal.";
+
+    for stream in listener.incoming() {
+        let mut stream = stream?;
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // The headers are read as well, since closing the connection with
+        // unread data may reset it before the browser gets the response.
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        // The request line looks like `GET /callback?code=... HTTP/1.1`
+        let url = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["GET", path, _] if path.starts_with('/') => redirect_uri.join(path)?,
+            _ => {
+                stream.write_all(NOT_FOUND.as_bytes())?;
+                continue;
+            }
+        };
+        if url.path() != redirect_uri.path() {
+            stream.write_all(NOT_FOUND.as_bytes())?;
+            continue;
+        }
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            DONE.len(),
+            DONE
+        )?;
+        return Ok(url.into());
+    }
+
+    Err(ClientError::Cli(
+        "stopped listening on the redirect URI".to_string(),
+    ))
+}
+
 // TODO: move to `lib.rs`
 #[inline]
 pub(in crate) fn join_ids<'a, T: 'a + IdType>(ids: impl IntoIterator<Item = &'a Id<T>>) -> String {
@@ -59,7 +375,30 @@ pub fn basic_auth(user: &str, password: &str) -> (String, String) {
 mod test {
     use super::*;
     use crate::{scopes, ClientCredsSpotify, Token};
-    use chrono::{prelude::*, Duration};
+    use chrono::prelude::*;
+
+    #[test]
+    fn test_token_error() {
+        let body = r#"{"error": "invalid_client", "error_description": "Invalid client secret"}"#;
+        let err = serde_json::from_str::<ApiError>(body).unwrap();
+        let err = token_error(HttpError::Api(err).into());
+        match err {
+            ClientError::InvalidAuth(msg) => {
+                assert_eq!(msg, "invalid_client: Invalid client secret")
+            }
+            err => panic!("unexpected error: {:?}", err),
+        }
+
+        let body = r#"{"error": "invalid_grant"}"#;
+        let err = serde_json::from_str::<ApiError>(body).unwrap();
+        let err = token_error(HttpError::Api(err).into());
+        assert!(matches!(err, ClientError::InvalidAuth(msg) if msg == "invalid_grant"));
+
+        // Other errors are left as they are
+        let err =
+            token_error(HttpError::StatusCode(500, "Internal Server Error".to_owned()).into());
+        assert!(matches!(err, ClientError::Http(_)));
+    }
 
     #[test]
     fn test_append_device_id_without_question_mark() {
@@ -80,6 +419,78 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_append_device_id_special_chars() {
+        let path = "me/player/play";
+        let device_id = Some("a b&c=d/é");
+        let new_path = append_device_id(path, device_id);
+        assert_eq!(new_path, "me/player/play?device_id=a+b%26c%3Dd%2F%C3%A9");
+    }
+
+    #[test]
+    fn test_append_device_id_seek() {
+        let path = "me/player/seek?position_ms=25000";
+        assert_eq!(append_device_id(path, None), path);
+        assert_eq!(
+            append_device_id(path, Some("fdafdsadfa")),
+            "me/player/seek?position_ms=25000&device_id=fdafdsadfa"
+        );
+    }
+
+    #[cfg(feature = "cli-server")]
+    #[test]
+    fn test_wait_for_redirect() {
+        use std::{io::Read, net::TcpStream, thread};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let redirect_uri = Url::parse(&format!("http://127.0.0.1:{}/callback", port)).unwrap();
+
+        let browser = thread::spawn(move || {
+            ["/favicon.ico", "/callback?code=abc&state=xyz"]
+                .iter()
+                .map(|path| {
+                    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+                    write!(
+                        stream,
+                        "GET {} HTTP/1.1\r\nHost: 127.0.0.1\r\nAccept: */*\r\n\r\n",
+                        path
+                    )
+                    .unwrap();
+                    let mut response = String::new();
+                    stream.read_to_string(&mut response).unwrap();
+                    response
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let url = wait_for_redirect(&listener, &redirect_uri).unwrap();
+        assert_eq!(
+            url,
+            format!("http://127.0.0.1:{}/callback?code=abc&state=xyz", port)
+        );
+
+        let responses = browser.join().unwrap();
+        assert!(responses[0].starts_with("HTTP/1.1 404"));
+        assert!(responses[1].starts_with("HTTP/1.1 200"));
+        assert!(responses[1].ends_with("go back to the terminal."));
+    }
+
+    #[cfg(feature = "cli-server")]
+    #[test]
+    fn test_redirect_listener() {
+        let listener = redirect_listener(&Url::parse("http://127.0.0.1:0/callback").unwrap());
+        assert!(listener.is_ok());
+
+        for uri in &[
+            "https://localhost:8888/callback",
+            "http://example.com:8888/callback",
+        ] {
+            let err = redirect_listener(&Url::parse(uri).unwrap()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
     #[test]
     fn test_bearer_auth() {
         let tok = Token {
@@ -99,6 +510,73 @@ mod test {
         assert_eq!(value, "Basic cmFtc2F5OjEyMzQ1Ng==");
     }
 
+    #[test]
+    fn test_redact_tokens() {
+        let body = r#"{"access_token":"secret","expires_in":3600,"refresh_token":"secret"}"#;
+        let redacted = redact_tokens(body);
+        assert!(!redacted.contains("secret"));
+        assert!(redacted.contains(r#""expires_in":3600"#));
+
+        // Malformed responses with tokens are hidden completely
+        assert_eq!(redact_tokens(r#"{"access_token": "secret""#), "<redacted>");
+
+        let body = r#"{"name":"Ferris"}"#;
+        assert_eq!(redact_tokens(body), body);
+    }
+
+    #[test]
+    fn test_response_snippet() {
+        let body = "ñ".repeat(RESPONSE_SNIPPET_LEN + 10);
+        let snippet = response_snippet(&body);
+        assert_eq!(snippet.chars().count(), RESPONSE_SNIPPET_LEN + 3);
+        assert!(snippet.ends_with("..."));
+
+        assert_eq!(response_snippet("{}"), "{}");
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let mut config = Config::default();
+        let err = HttpError::RateLimited(Some(5));
+        assert_eq!(retry_delay(&config, &err, 0, true), None);
+
+        config.retry_on_rate_limit = true;
+        config.max_retries = 2;
+        assert_eq!(
+            retry_delay(&config, &err, 0, false),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retry_delay(&config, &err, 2, false), None);
+
+        // Exponential backoff when the header is missing
+        let err = HttpError::RateLimited(None);
+        assert_eq!(
+            retry_delay(&config, &err, 0, false),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            retry_delay(&config, &err, 1, false),
+            Some(Duration::from_secs(2))
+        );
+
+        // Connection errors are only retried for idempotent requests
+        let err = HttpError::Connection("connection reset".to_string());
+        assert_eq!(retry_delay(&config, &err, 0, true), None);
+        config.retry_on_transient = true;
+        assert_eq!(
+            retry_delay(&config, &err, 1, true),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(retry_delay(&config, &err, 0, false), None);
+        assert_eq!(retry_delay(&config, &err, 2, true), None);
+
+        // Other errors are never retried
+        let err = HttpError::Unauthorized;
+        assert_eq!(retry_delay(&config, &err, 0, true), None);
+        let err = HttpError::Request("timed out".to_string());
+        assert_eq!(retry_delay(&config, &err, 0, true), None);
+    }
+
     #[test]
     fn test_endpoint_url() {
         let spotify = ClientCredsSpotify::default();
@@ -116,11 +594,40 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_is_authenticated() {
+        let spotify = ClientCredsSpotify::default();
+        assert!(!spotify.is_authenticated());
+
+        let tok = Token {
+            expires_in: chrono::Duration::seconds(3600),
+            expires_at: Some(Utc::now() + chrono::Duration::seconds(3600)),
+            ..Default::default()
+        };
+        let spotify = ClientCredsSpotify::from_token(tok);
+        assert!(spotify.is_authenticated());
+
+        let tok = Token {
+            expires_at: Some(Utc::now() - chrono::Duration::seconds(1)),
+            ..Default::default()
+        };
+        let spotify = ClientCredsSpotify::from_token(tok);
+        assert!(!spotify.is_authenticated());
+
+        // Tokens about to expire are considered expired already
+        let tok = Token {
+            expires_at: Some(Utc::now() + chrono::Duration::seconds(5)),
+            ..Default::default()
+        };
+        let spotify = ClientCredsSpotify::from_token(tok);
+        assert!(!spotify.is_authenticated());
+    }
+
     #[test]
     fn test_auth_headers() {
         let tok = Token {
             access_token: "test-access_token".to_string(),
-            expires_in: Duration::seconds(1),
+            expires_in: chrono::Duration::seconds(1),
             expires_at: Some(Utc::now()),
             scopes: scopes!("playlist-read-private"),
             refresh_token: Some("...".to_string()),
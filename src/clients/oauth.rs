@@ -1,23 +1,28 @@
 use crate::{
     clients::{
         append_device_id, convert_result, join_ids,
-        pagination::{paginate, Paginator},
-        BaseClient,
+        pagination::{paginate, paginate_with_cursor, Paginator},
+        require_scopes, BaseClient,
     },
     http::Query,
-    macros::{build_json, build_map},
+    macros::{build_json, build_map, scopes},
     model::*,
-    ClientResult, OAuth, Token,
+    ClientError, ClientResult, OAuth, Token,
 };
 
 use std::time;
 
-use log::error;
 use maybe_async::maybe_async;
 use rspotify_model::idtypes::PlayContextIdType;
 use serde_json::{json, Map};
 use url::Url;
 
+#[cfg(feature = "cli-server")]
+use crate::clients::{redirect_listener, wait_for_redirect};
+
+/// The maximum number of tracks that can be saved in a single request.
+const SAVED_TRACKS_CHUNK: usize = 50;
+
 /// This trait implements the methods available strictly to clients with user
 /// authorization, including some parts of the authentication flow that are
 /// shared, and the endpoints.
@@ -39,11 +44,44 @@ pub trait OAuthClient: BaseClient {
     /// token will be saved internally.
     async fn refresh_token(&mut self, refresh_token: &str) -> ClientResult<()>;
 
+    /// Refreshes the current access token with its refresh token, but only if
+    /// it's expired or about to expire within
+    /// [`Config::token_expiry_margin`](crate::Config::token_expiry_margin).
+    /// Returns whether a refresh was performed.
+    ///
+    /// This is useful before a batch of requests, so that the token doesn't
+    /// have to be checked for each of them. Fails with
+    /// [`ClientError::InvalidAuth`] if the token has to be refreshed but there
+    /// isn't a refresh token available.
+    async fn refresh_token_if_needed(&mut self) -> ClientResult<bool> {
+        if self.is_authenticated() {
+            return Ok(false);
+        }
+
+        let refresh_token = self
+            .get_token()
+            .and_then(|token| token.refresh_token.clone())
+            .ok_or_else(|| {
+                ClientError::InvalidAuth("there's no refresh token available".to_string())
+            })?;
+        self.refresh_token(&refresh_token).await?;
+
+        Ok(true)
+    }
+
     /// Tries to read the cache file's token, which may not exist.
+    ///
+    /// Similarly to [`BaseClient::write_token_cache`], this will already check
+    /// if the cached token is enabled and return `None` in case it isn't.
     async fn read_token_cache(&mut self) -> Option<Token> {
-        let tok = Token::from_cache(&self.get_config().cache_path)?;
+        if !self.get_config().token_cached {
+            return None;
+        }
 
-        if !self.get_oauth().scopes.is_subset(&tok.scopes) || tok.is_expired() {
+        let tok = self.get_config().get_cache_manager().read().ok()??;
+        let margin = self.get_config().token_expiry_margin;
+
+        if !self.get_oauth().scopes.is_subset(&tok.scopes) || tok.is_expired_with_margin(margin) {
             // Invalid token, since it doesn't have at least the currently
             // required scopes or it's expired.
             None
@@ -54,6 +92,9 @@ pub trait OAuthClient: BaseClient {
 
     /// Parse the response code in the given response url. If the URL cannot be
     /// parsed or the `code` parameter is not present, this will return `None`.
+    ///
+    /// Note that this doesn't check the `state` parameter; consider using
+    /// [`Self::parse_response_code_and_verify_state`] instead.
     fn parse_response_code(&self, url: &str) -> Option<String> {
         let url = Url::parse(url).ok()?;
         let mut params = url.query_pairs();
@@ -61,14 +102,38 @@ pub trait OAuthClient: BaseClient {
         Some(url.to_string())
     }
 
+    /// Same as [`Self::parse_response_code`], but it also makes sure that the
+    /// `state` parameter in the URL is the same as the one in the client's
+    /// [`OAuth`], failing with [`ClientError::InvalidAuth`] otherwise.
+    ///
+    /// Verifying the state protects against cross-site request forgery, since
+    /// it guarantees that the response corresponds to the authorization
+    /// request sent by this client.
+    fn parse_response_code_and_verify_state(&self, url: &str) -> ClientResult<String> {
+        let url = Url::parse(url)?;
+        let param = |name| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, val)| val.into_owned())
+        };
+
+        if param("state").as_deref() != Some(self.get_oauth().state.as_str()) {
+            return Err(ClientError::InvalidAuth(
+                "the state in the response doesn't match the one sent".to_string(),
+            ));
+        }
+
+        param("code").ok_or_else(|| {
+            ClientError::InvalidAuth("the response doesn't include a code".to_string())
+        })
+    }
+
     /// Tries to open the authorization URL in the user's browser, and returns
     /// the obtained code.
     ///
     /// Note: this method requires the `cli` feature.
     #[cfg(feature = "cli")]
     fn get_code_from_user(&self, url: &str) -> ClientResult<String> {
-        use crate::ClientError;
-
         match webbrowser::open(url) {
             Ok(_) => println!("Opened {} in your browser.", url),
             Err(why) => eprintln!(
@@ -81,11 +146,42 @@ pub trait OAuthClient: BaseClient {
         println!("Please enter the URL you were redirected to: ");
         let mut input = String::new();
         std::io::stdin().read_line(&mut input)?;
-        let code = self
-            .parse_response_code(&input)
-            .ok_or_else(|| ClientError::Cli("unable to parse the response code".to_string()))?;
+        self.parse_response_code_and_verify_state(input.trim())
+    }
+
+    /// Same as [`Self::get_code_from_user`], but the code is obtained by
+    /// listening on the redirect URI until the user's browser is redirected
+    /// to it, instead of asking the user for it. It falls back to
+    /// [`Self::get_code_from_user`] in case it can't listen on it, like when
+    /// the redirect URI isn't a local `http` one or its port is in use.
+    ///
+    /// Note: this method requires the `cli-server` feature.
+    #[cfg(feature = "cli-server")]
+    fn get_code_from_listener(&self, url: &str) -> ClientResult<String> {
+        let redirect_uri = Url::parse(&self.get_oauth().redirect_uri)?;
+        let listener = match redirect_listener(&redirect_uri) {
+            Ok(listener) => listener,
+            Err(why) => {
+                eprintln!(
+                    "Couldn't listen on the redirect URI {}: {}",
+                    redirect_uri, why
+                );
+                return self.get_code_from_user(url);
+            }
+        };
+
+        match webbrowser::open(url) {
+            Ok(_) => println!("Opened {} in your browser.", url),
+            Err(why) => eprintln!(
+                "Error when trying to open an URL in your browser: {:?}. \
+                 Please navigate here manually: {}",
+                why, url
+            ),
+        }
 
-        Ok(code)
+        println!("Waiting for the authorization at {}...", redirect_uri);
+        let response = wait_for_redirect(&listener, &redirect_uri)?;
+        self.parse_response_code_and_verify_state(&response)
     }
 
     /// Opens up the authorization URL in the user's browser so that it can
@@ -99,8 +195,8 @@ pub trait OAuthClient: BaseClient {
     async fn prompt_for_token(&mut self, url: &str) -> ClientResult<()> {
         match self.read_token_cache().await {
             // TODO: shouldn't this also refresh the obtained token?
-            Some(ref mut new_token) => {
-                self.get_token_mut().replace(new_token);
+            Some(new_token) => {
+                *self.get_token_mut() = Some(new_token);
             }
             // Otherwise following the usual procedure to get the token.
             None => {
@@ -113,6 +209,28 @@ pub trait OAuthClient: BaseClient {
         self.write_token_cache()
     }
 
+    /// Same as [`Self::prompt_for_token`], but the redirect URI is obtained
+    /// with a local server listening on it instead of asking the user for it.
+    /// See [`Self::get_code_from_listener`].
+    ///
+    /// Note: this method requires the `cli-server` feature.
+    #[cfg(feature = "cli-server")]
+    #[maybe_async]
+    async fn prompt_for_token_with_listener(&mut self, url: &str) -> ClientResult<()> {
+        match self.read_token_cache().await {
+            Some(new_token) => {
+                *self.get_token_mut() = Some(new_token);
+            }
+            None => {
+                let code = self.get_code_from_listener(url)?;
+                // Will write to the cache file if successful
+                self.request_token(&code).await?;
+            }
+        }
+
+        self.write_token_cache()
+    }
+
     /// Get current user playlists without required getting his profile.
     ///
     /// Parameters:
@@ -147,14 +265,17 @@ pub trait OAuthClient: BaseClient {
         convert_result(&result)
     }
 
-    /// Creates a playlist for a user.
+    /// Creates a playlist for a user, returning the created playlist.
     ///
     /// Parameters:
     /// - user_id - the id of the user
     /// - name - the name of the playlist
-    /// - public - is the created playlist public
+    /// - public - is the created playlist public. Spotify makes it public by
+    ///   default.
+    /// - collaborative - if the playlist will be collaborative. Collaborative
+    ///   playlists must be private, so `public` has to be `Some(false)` as
+    ///   well. Otherwise, [`ClientError::InvalidArgument`] is returned.
     /// - description - the description of the playlist
-    /// - collaborative - if the playlist will be collaborative
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-create-playlist)
     async fn user_playlist_create(
@@ -165,6 +286,12 @@ pub trait OAuthClient: BaseClient {
         collaborative: Option<bool>,
         description: Option<&str>,
     ) -> ClientResult<FullPlaylist> {
+        if collaborative == Some(true) && public != Some(false) {
+            return Err(ClientError::InvalidArgument(
+                "collaborative playlists must be private".to_string(),
+            ));
+        }
+
         let params = build_json! {
             "name": name,
             optional "public": public,
@@ -177,24 +304,26 @@ pub trait OAuthClient: BaseClient {
         convert_result(&result)
     }
 
-    /// Changes a playlist's name and/or public/private state.
+    /// Changes a playlist's name, visibility, collaborative state and/or
+    /// description. Only the given details are changed.
     ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
     /// - name - optional name of the playlist
     /// - public - optional is the playlist public
-    /// - collaborative - optional is the playlist collaborative
+    /// - collaborative - optional is the playlist collaborative. Only private
+    ///   playlists can be collaborative.
     /// - description - optional description of the playlist
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-change-playlist-details)
     async fn playlist_change_detail(
         &self,
-        playlist_id: &str,
+        playlist_id: &PlaylistId,
         name: Option<&str>,
         public: Option<bool>,
-        description: Option<&str>,
         collaborative: Option<bool>,
-    ) -> ClientResult<String> {
+        description: Option<&str>,
+    ) -> ClientResult<()> {
         let params = build_json! {
             optional "name": name,
             optional "public": public,
@@ -202,8 +331,38 @@ pub trait OAuthClient: BaseClient {
             optional "description": description,
         };
 
-        let url = format!("playlists/{}", playlist_id);
-        self.endpoint_put(&url, &params).await
+        let url = format!("playlists/{}", playlist_id.id());
+        self.endpoint_put(&url, &params).await?;
+
+        Ok(())
+    }
+
+    /// Replaces the cover image of a playlist.
+    ///
+    /// Parameters:
+    /// - playlist_id - the id of the playlist
+    /// - image_b64 - the image as a base64-encoded JPEG, with a maximum size
+    ///   of 256 KB. If it's empty, [`ClientError::InvalidArgument`] is
+    ///   returned.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-upload-custom-playlist-cover)
+    async fn playlist_upload_cover_image(
+        &self,
+        playlist_id: &PlaylistId,
+        image_b64: &str,
+    ) -> ClientResult<()> {
+        if image_b64.trim().is_empty() {
+            return Err(ClientError::InvalidArgument(
+                "the cover image can't be empty".to_string(),
+            ));
+        }
+        require_scopes(self.get_token(), &scopes!("ugc-image-upload"))?;
+
+        let url = format!("playlists/{}/images", playlist_id.id());
+        self.endpoint_put_bytes(&url, "image/jpeg", image_b64.as_bytes())
+            .await?;
+
+        Ok(())
     }
 
     /// Unfollows (deletes) a playlist for a user.
@@ -360,7 +519,12 @@ pub trait OAuthClient: BaseClient {
     ///    ]
     /// }
     /// ```
-    /// - snapshot_id: optional id of the playlist snapshot
+    /// - snapshot_id: optional id of the playlist snapshot, so that the
+    ///   positions refer to that version of the playlist even if it has been
+    ///   modified since.
+    ///
+    /// The positions are zero-based, and they're sorted and deduplicated
+    /// before sending them, as required by Spotify.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-tracks-playlist)
     async fn playlist_remove_specific_occurrences_of_tracks<'a>(
@@ -372,9 +536,13 @@ pub trait OAuthClient: BaseClient {
         let tracks = tracks
             .into_iter()
             .map(|track| {
+                let mut positions = track.positions.clone();
+                positions.sort_unstable();
+                positions.dedup();
+
                 let mut map = Map::new();
                 map.insert("uri".to_owned(), track.id.uri().into());
-                map.insert("positions".to_owned(), json!(track.positions));
+                map.insert("positions".to_owned(), json!(positions));
                 map
             })
             .collect::<Vec<_>>();
@@ -393,6 +561,8 @@ pub trait OAuthClient: BaseClient {
     ///
     /// Parameters:
     /// - playlist_id - the id of the playlist
+    /// - public - whether the playlist will be included in the user's public
+    ///   playlists. If it's `None`, Spotify follows it publicly.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-follow-playlist)
     async fn playlist_follow(
@@ -450,8 +620,8 @@ pub trait OAuthClient: BaseClient {
     /// - offset - the index of the first album to return
     /// - market - Provide this parameter if you want to apply Track Relinking.
     ///
-    /// See [`Self::current_user_saved_albums`] for a manually paginated version
-    /// of this.
+    /// See [`Self::current_user_saved_albums_manual`] for a manually paginated
+    /// version of this.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-albums)
     fn current_user_saved_albums(&self) -> Paginator<'_, ClientResult<SavedAlbum>> {
@@ -462,11 +632,14 @@ pub trait OAuthClient: BaseClient {
     }
 
     /// The manually paginated version of [`Self::current_user_saved_albums`].
+    /// Note that the maximum `limit` is 50.
     async fn current_user_saved_albums_manual(
         &self,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<SavedAlbum>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
         let params = build_map! {
@@ -498,11 +671,14 @@ pub trait OAuthClient: BaseClient {
     }
 
     /// The manually paginated version of [`Self::current_user_saved_tracks`].
+    /// Note that the maximum `limit` is 50.
     async fn current_user_saved_tracks_manual(
         &self,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<SavedTrack>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
         let limit = limit.map(|s| s.to_string());
         let offset = offset.map(|s| s.to_string());
         let params = build_map! {
@@ -514,18 +690,78 @@ pub trait OAuthClient: BaseClient {
         convert_result(&result)
     }
 
+    /// Get a list of the episodes saved in the current Spotify user's
+    /// library.
+    ///
+    /// Requires the `user-library-read` and `user-read-playback-position`
+    /// scopes.
+    ///
+    /// See [`Self::current_user_saved_episodes_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-episodes)
+    fn current_user_saved_episodes(&self) -> Paginator<'_, ClientResult<SavedEpisode>> {
+        paginate(
+            move |limit, offset| self.current_user_saved_episodes_manual(Some(limit), Some(offset)),
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of [`Self::current_user_saved_episodes`].
+    /// Note that the maximum `limit` is 50.
+    async fn current_user_saved_episodes_manual(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SavedEpisode>> {
+        require_scopes(
+            self.get_token(),
+            &scopes!("user-library-read", "user-read-playback-position"),
+        )?;
+
+        let limit = limit.map(|s| s.to_string());
+        let offset = offset.map(|s| s.to_string());
+        let params = build_map! {
+            optional "limit": limit.as_deref(),
+            optional "offset": offset.as_deref(),
+        };
+
+        let result = self.endpoint_get("me/episodes", &params).await?;
+        convert_result(&result)
+    }
+
     /// Gets a list of the artists followed by the current authorized user.
     ///
+    /// This endpoint uses cursor-based pagination, which is followed
+    /// automatically by continuing after the `after` cursor of each page.
+    ///
+    /// See [`Self::current_user_followed_artists_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-followed)
+    fn current_user_followed_artists(&self) -> Paginator<'_, ClientResult<FullArtist>> {
+        paginate_with_cursor(
+            move |after, limit| followed_artists_page(self, after, limit),
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_followed_artists`]. The next page may be
+    /// requested by passing the `after` cursor of the previous one.
+    ///
     /// Parameters:
     /// - after - the last artist ID retrieved from the previous request
-    /// - limit - the number of tracks to return
+    /// - limit - the number of artists to return. Maximum: 50.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-followed)
-    async fn current_user_followed_artists(
+    async fn current_user_followed_artists_manual(
         &self,
         after: Option<&str>,
         limit: Option<u32>,
     ) -> ClientResult<CursorBasedPage<FullArtist>> {
+        require_scopes(self.get_token(), &scopes!("user-follow-read"))?;
+
         let limit = limit.map(|s| s.to_string());
         let params = build_map! {
             "type": Type::Artist.as_ref(),
@@ -547,6 +783,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         track_ids: impl IntoIterator<Item = &'a TrackId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
         let url = format!("me/tracks/?ids={}", join_ids(track_ids));
         self.endpoint_delete(&url, &json!({})).await?;
 
@@ -564,6 +802,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         track_ids: impl IntoIterator<Item = &'a TrackId> + 'a,
     ) -> ClientResult<Vec<bool>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
         let url = format!("me/tracks/contains/?ids={}", join_ids(track_ids));
         let result = self.endpoint_get(&url, &Query::new()).await?;
         convert_result(&result)
@@ -579,12 +819,41 @@ pub trait OAuthClient: BaseClient {
         &self,
         track_ids: impl IntoIterator<Item = &'a TrackId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
         let url = format!("me/tracks/?ids={}", join_ids(track_ids));
         self.endpoint_put(&url, &json!({})).await?;
 
         Ok(())
     }
 
+    /// Same as [`Self::current_user_saved_tracks_add`], but for any number of
+    /// tracks. They're split into chunks of 50, the maximum allowed by
+    /// Spotify, which are saved with sequential requests.
+    ///
+    /// If a request fails, [`ClientError::Chunk`] is returned with the index
+    /// of the chunk that failed. The previous chunks will have been saved
+    /// already, and the next ones won't be attempted.
+    ///
+    /// Parameters:
+    /// - track_ids - a list of track URIs, URLs or IDs
+    async fn current_user_saved_tracks_add_all<'a>(
+        &self,
+        track_ids: impl IntoIterator<Item = &'a TrackId> + 'a,
+    ) -> ClientResult<()> {
+        let track_ids = track_ids.into_iter().collect::<Vec<_>>();
+        for (index, chunk) in track_ids.chunks(SAVED_TRACKS_CHUNK).enumerate() {
+            self.current_user_saved_tracks_add(chunk.iter().copied())
+                .await
+                .map_err(|err| ClientError::Chunk {
+                    index,
+                    source: Box::new(err),
+                })?;
+        }
+
+        Ok(())
+    }
+
     /// Get the current user's top artists.
     ///
     /// Parameters:
@@ -672,17 +941,29 @@ pub trait OAuthClient: BaseClient {
     /// Get the current user's recently played tracks.
     ///
     /// Parameters:
-    /// - limit - the number of entities to return
+    /// - limit - the number of entities to return. Maximum: 50
+    /// - time_limit - only return the items played before or after the given
+    ///   time. The `cursors` of the response can be used to request the next
+    ///   page.
     ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-the-users-currently-playing-track)
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
     async fn current_user_recently_played(
         &self,
         limit: Option<u32>,
+        time_limit: Option<TimeLimits>,
     ) -> ClientResult<CursorBasedPage<PlayHistory>> {
         let limit = limit.map(|x| x.to_string());
-        let params = build_map! {
+        let mut params = build_map! {
             optional "limit": limit.as_deref(),
         };
+        let time_limit = match time_limit {
+            Some(TimeLimits::Before(time)) => Some(("before", time.timestamp_millis().to_string())),
+            Some(TimeLimits::After(time)) => Some(("after", time.timestamp_millis().to_string())),
+            None => None,
+        };
+        if let Some((key, value)) = &time_limit {
+            params.insert(key, value);
+        }
 
         let result = self
             .endpoint_get("me/player/recently-played", &params)
@@ -700,6 +981,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         album_ids: impl IntoIterator<Item = &'a AlbumId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
         let url = format!("me/albums/?ids={}", join_ids(album_ids));
         self.endpoint_put(&url, &json!({})).await?;
 
@@ -716,6 +999,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         album_ids: impl IntoIterator<Item = &'a AlbumId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
         let url = format!("me/albums/?ids={}", join_ids(album_ids));
         self.endpoint_delete(&url, &json!({})).await?;
 
@@ -733,11 +1018,104 @@ pub trait OAuthClient: BaseClient {
         &self,
         album_ids: impl IntoIterator<Item = &'a AlbumId> + 'a,
     ) -> ClientResult<Vec<bool>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
         let url = format!("me/albums/contains/?ids={}", join_ids(album_ids));
         let result = self.endpoint_get(&url, &Query::new()).await?;
         convert_result(&result)
     }
 
+    /// Gets a list of the audiobooks saved in the current authorized user's
+    /// library.
+    ///
+    /// See [`Self::current_user_saved_audiobooks_manual`] for a manually
+    /// paginated version of this.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-users-saved-audiobooks)
+    fn current_user_saved_audiobooks(&self) -> Paginator<'_, ClientResult<SimplifiedAudiobook>> {
+        paginate(
+            move |limit, offset| {
+                self.current_user_saved_audiobooks_manual(Some(limit), Some(offset))
+            },
+            self.get_config().pagination_chunks,
+        )
+    }
+
+    /// The manually paginated version of
+    /// [`Self::current_user_saved_audiobooks`]. Note that the maximum `limit`
+    /// is 50.
+    async fn current_user_saved_audiobooks_manual(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> ClientResult<Page<SimplifiedAudiobook>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
+        let limit = limit.map(|s| s.to_string());
+        let offset = offset.map(|s| s.to_string());
+        let params = build_map! {
+            optional "limit": limit.as_deref(),
+            optional "offset": offset.as_deref(),
+        };
+
+        let result = self.endpoint_get("me/audiobooks", &params).await?;
+        convert_result(&result)
+    }
+
+    /// Add one or more audiobooks to the current user's library.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-save-audiobooks-user)
+    async fn current_user_saved_audiobooks_add<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = &'a AudiobookId> + 'a,
+    ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
+        let url = format!("me/audiobooks?ids={}", join_ids(audiobook_ids));
+        self.endpoint_put(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Remove one or more audiobooks from the current user's library.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-remove-audiobooks-user)
+    async fn current_user_saved_audiobooks_delete<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = &'a AudiobookId> + 'a,
+    ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
+        let url = format!("me/audiobooks?ids={}", join_ids(audiobook_ids));
+        self.endpoint_delete(&url, &json!({})).await?;
+
+        Ok(())
+    }
+
+    /// Check if one or more audiobooks are already saved in the current
+    /// user's library.
+    ///
+    /// Parameters:
+    /// - audiobook_ids - a list of audiobook URIs, URLs or IDs
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-check-users-saved-audiobooks)
+    async fn current_user_saved_audiobooks_contains<'a>(
+        &self,
+        audiobook_ids: impl IntoIterator<Item = &'a AudiobookId> + 'a,
+    ) -> ClientResult<Vec<bool>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
+        let url = format!("me/audiobooks/contains?ids={}", join_ids(audiobook_ids));
+        let result = self.endpoint_get(&url, &Query::new()).await?;
+        convert_result(&result)
+    }
+
     /// Follow one or more artists.
     ///
     /// Parameters:
@@ -748,6 +1126,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         artist_ids: impl IntoIterator<Item = &'a ArtistId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-follow-modify"))?;
+
         let url = format!("me/following?type=artist&ids={}", join_ids(artist_ids));
         self.endpoint_put(&url, &json!({})).await?;
 
@@ -764,6 +1144,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         artist_ids: impl IntoIterator<Item = &'a ArtistId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-follow-modify"))?;
+
         let url = format!("me/following?type=artist&ids={}", join_ids(artist_ids));
         self.endpoint_delete(&url, &json!({})).await?;
 
@@ -781,6 +1163,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         artist_ids: impl IntoIterator<Item = &'a ArtistId> + 'a,
     ) -> ClientResult<Vec<bool>> {
+        require_scopes(self.get_token(), &scopes!("user-follow-read"))?;
+
         let url = format!(
             "me/following/contains?type=artist&ids={}",
             join_ids(artist_ids)
@@ -799,6 +1183,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         user_ids: impl IntoIterator<Item = &'a UserId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-follow-modify"))?;
+
         let url = format!("me/following?type=user&ids={}", join_ids(user_ids));
         self.endpoint_put(&url, &json!({})).await?;
 
@@ -815,6 +1201,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         user_ids: impl IntoIterator<Item = &'a UserId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-follow-modify"))?;
+
         let url = format!("me/following?type=user&ids={}", join_ids(user_ids));
         self.endpoint_delete(&url, &json!({})).await?;
 
@@ -842,7 +1230,7 @@ pub trait OAuthClient: BaseClient {
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-information-about-the-users-current-playback)
     async fn current_playback<'a>(
         &self,
-        country: Option<&Market>,
+        market: Option<&Market>,
         additional_types: Option<impl IntoIterator<Item = &'a AdditionalType> + 'a>,
     ) -> ClientResult<Option<CurrentPlaybackContext>> {
         let additional_types = additional_types.map(|x| {
@@ -852,7 +1240,7 @@ pub trait OAuthClient: BaseClient {
                 .join(",")
         });
         let params = build_map! {
-            optional "country": country.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
             optional "additional_types": additional_types.as_deref(),
         };
 
@@ -872,7 +1260,7 @@ pub trait OAuthClient: BaseClient {
     ///   your client supports besides the default track type. Valid types are:
     ///   `track` and `episode`.
     ///
-    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-recently-played)
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-the-users-currently-playing-track)
     async fn current_playing<'a>(
         &self,
         market: Option<&'a Market>,
@@ -885,7 +1273,7 @@ pub trait OAuthClient: BaseClient {
                 .join(",")
         });
         let params = build_map! {
-            optional "market": market.map(|x| x.as_ref()),
+            optional "market": self.get_config().market_or_default(market).map(|x| x.as_ref()),
             optional "additional_types": additional_types.as_ref(),
         };
 
@@ -899,6 +1287,17 @@ pub trait OAuthClient: BaseClient {
         }
     }
 
+    /// Get the list of objects that make up the user's queue, including the
+    /// one that's currently playing.
+    ///
+    /// Requires the `user-read-playback-state` scope.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-get-queue)
+    async fn current_user_queue(&self) -> ClientResult<CurrentUserQueue> {
+        let result = self.endpoint_get("me/player/queue", &Query::new()).await?;
+        convert_result(&result)
+    }
+
     /// Transfer a User’s Playback.
     ///
     /// Note: Although an array is accepted, only a single device_id is
@@ -906,7 +1305,8 @@ pub trait OAuthClient: BaseClient {
     ///
     /// Parameters:
     /// - device_id - transfer playback to this device
-    /// - force_play - true: after transfer, play. false: keep current state.
+    /// - play - true: after transfer, play. false: pause. If omitted, the
+    ///   current playback state is kept.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-transfer-a-users-playback)
     async fn transfer_playback(&self, device_id: &str, play: Option<bool>) -> ClientResult<()> {
@@ -947,29 +1347,37 @@ pub trait OAuthClient: BaseClient {
                 Offset::Position(position) => json!({ "position": position }),
                 Offset::Uri(uri) => json!({ "uri": uri.uri() }),
             }),
-            optional "position_ms": position_ms,
-
+            optional "position_ms": position_ms.map(|x| x.as_millis() as u64),
         };
 
         let url = append_device_id("me/player/play", device_id);
-        self.put(&url, None, &params).await?;
+        self.endpoint_put(&url, &params).await?;
 
         Ok(())
     }
 
-    async fn start_uris_playback<'a, T: PlayableIdType + 'a>(
+    /// Same as [`Self::start_context_playback`], but plays the given list of
+    /// tracks or episodes instead of a context. `position_ms` is in
+    /// milliseconds.
+    ///
+    /// Tracks and episodes may be mixed in the same list by converting their
+    /// ids into [`PlayableId`] first. Likewise, the offset may point to
+    /// either of them.
+    ///
+    /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-start-a-users-playback)
+    async fn start_uris_playback<'a, I: Into<PlayableId<'a>>>(
         &self,
-        uris: impl IntoIterator<Item = &'a Id<T>> + 'a,
+        uris: impl IntoIterator<Item = I> + 'a,
         device_id: Option<&str>,
-        offset: Option<crate::model::Offset<T>>,
+        offset: Option<PlayableOffset<'a>>,
         position_ms: Option<u32>,
     ) -> ClientResult<()> {
         let params = build_json! {
-            "uris": uris.into_iter().map(|id| id.uri()).collect::<Vec<_>>(),
+            "uris": uris.into_iter().map(|id| id.into().uri()).collect::<Vec<_>>(),
             optional "position_ms": position_ms,
             optional "offset": offset.map(|x| match x {
-                Offset::Position(position) => json!({ "position": position }),
-                Offset::Uri(uri) => json!({ "uri": uri.uri() }),
+                PlayableOffset::Position(position) => json!({ "position": position }),
+                PlayableOffset::Uri(uri) => json!({ "uri": uri.uri() }),
             }),
         };
 
@@ -1018,14 +1426,16 @@ pub trait OAuthClient: BaseClient {
         Ok(())
     }
 
-    /// Seek To Position In Currently Playing Track.
+    /// Seek To Position In Currently Playing Item, which may be either a
+    /// track or an episode.
     ///
     /// Parameters:
     /// - position_ms - position in milliseconds to seek to
-    /// - device_id - device target for playback
+    /// - device_id - device target for playback. If omitted, the user's
+    ///   currently active device is used.
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-seek-to-position-in-currently-playing-track)
-    async fn seek_track(&self, position_ms: u32, device_id: Option<&str>) -> ClientResult<()> {
+    async fn seek_position(&self, position_ms: u32, device_id: Option<&str>) -> ClientResult<()> {
         let url = append_device_id(
             &format!("me/player/seek?position_ms={}", position_ms),
             device_id,
@@ -1035,6 +1445,14 @@ pub trait OAuthClient: BaseClient {
         Ok(())
     }
 
+    /// Seek To Position In Currently Playing Track.
+    ///
+    /// See [`Self::seek_position`], which also works for episodes.
+    #[deprecated(note = "renamed to `seek_position`, since it also works for episodes")]
+    async fn seek_track(&self, position_ms: u32, device_id: Option<&str>) -> ClientResult<()> {
+        self.seek_position(position_ms, device_id).await
+    }
+
     /// Set Repeat Mode On User’s Playback.
     ///
     /// Parameters:
@@ -1055,13 +1473,17 @@ pub trait OAuthClient: BaseClient {
     /// Set Volume For User’s Playback.
     ///
     /// Parameters:
-    /// - volume_percent - volume between 0 and 100
+    /// - volume_percent - volume between 0 and 100, inclusive. Otherwise,
+    ///   [`ClientError::InvalidArgument`] is returned.
     /// - device_id - device target for playback
     ///
     /// [Reference](https://developer.spotify.com/documentation/web-api/reference/#endpoint-set-volume-for-users-playback)
     async fn volume(&self, volume_percent: u8, device_id: Option<&str>) -> ClientResult<()> {
-        if volume_percent > 100u8 {
-            error!("volume must be between 0 and 100, inclusive");
+        if volume_percent > 100 {
+            return Err(ClientError::InvalidArgument(format!(
+                "the volume must be between 0 and 100, got {}",
+                volume_percent
+            )));
         }
         let url = append_device_id(
             &format!("me/player/volume?volume_percent={}", volume_percent),
@@ -1117,6 +1539,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         show_ids: impl IntoIterator<Item = &'a ShowId> + 'a,
     ) -> ClientResult<()> {
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
         let url = format!("me/shows/?ids={}", join_ids(show_ids));
         self.endpoint_put(&url, &json!({})).await?;
 
@@ -1143,12 +1567,15 @@ pub trait OAuthClient: BaseClient {
         )
     }
 
-    /// The manually paginated version of [`Self::get_saved_show`].
+    /// The manually paginated version of [`Self::get_saved_show`]. Note that
+    /// the maximum `limit` is 50.
     async fn get_saved_show_manual(
         &self,
         limit: Option<u32>,
         offset: Option<u32>,
     ) -> ClientResult<Page<Show>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
         let limit = limit.map(|x| x.to_string());
         let offset = offset.map(|x| x.to_string());
         let params = build_map! {
@@ -1170,6 +1597,8 @@ pub trait OAuthClient: BaseClient {
         &self,
         ids: impl IntoIterator<Item = &'a ShowId> + 'a,
     ) -> ClientResult<Vec<bool>> {
+        require_scopes(self.get_token(), &scopes!("user-library-read"))?;
+
         let ids = join_ids(ids);
         let params = build_map! {
             "ids": &ids,
@@ -1189,14 +1618,31 @@ pub trait OAuthClient: BaseClient {
     async fn remove_users_saved_shows<'a>(
         &self,
         show_ids: impl IntoIterator<Item = &'a ShowId> + 'a,
-        country: Option<&Market>,
+        market: Option<&Market>,
     ) -> ClientResult<()> {
-        let url = format!("me/shows?ids={}", join_ids(show_ids));
-        let params = build_json! {
-            optional "country": country.map(|x| x.as_ref())
-        };
-        self.endpoint_delete(&url, &params).await?;
+        require_scopes(self.get_token(), &scopes!("user-library-modify"))?;
+
+        // Unlike in most endpoints, the market is passed in the query even
+        // though this is a `DELETE` request.
+        let mut url = format!("me/shows?ids={}", join_ids(show_ids));
+        if let Some(market) = self.get_config().market_or_default(market) {
+            url.push_str(&format!("&market={}", market.as_ref()));
+        }
+        self.endpoint_delete(&url, &json!({})).await?;
 
         Ok(())
     }
 }
+
+/// Requests a single page of the followed artists. The cursor is owned so that
+/// the request may be used by [`paginate_with_cursor`].
+#[maybe_async]
+async fn followed_artists_page<C: OAuthClient>(
+    client: &C,
+    after: Option<String>,
+    limit: u32,
+) -> ClientResult<CursorBasedPage<FullArtist>> {
+    client
+        .current_user_followed_artists_manual(after.as_deref(), Some(limit))
+        .await
+}
@@ -1,6 +1,10 @@
 //! Synchronous implementation of automatic pagination requests.
 
-use crate::{model::Page, ClientError, ClientResult};
+use super::{AfterCursor, PageCursor, Paginated};
+use crate::{
+    model::{CursorBasedPage, Page},
+    ClientError, ClientResult,
+};
 
 /// Alias for `Iterator<Item = T>`, since sync mode is enabled.
 pub type Paginator<'a, T> = Box<dyn Iterator<Item = T> + 'a>;
@@ -15,20 +19,48 @@ where
 {
     let pages = PageIterator {
         req,
-        offset: 0,
-        done: false,
+        cursor: PageCursor::default(),
         page_size,
     };
 
     Box::new(pages.flat_map(|result| ResultIter::new(result.map(|page| page.items.into_iter()))))
 }
 
-/// Iterator that repeatedly calls a function that returns a page until an empty
+/// This is used to handle cursor-based paginated requests automatically. The
+/// request receives the `after` cursor of the previous page, if any, and the
+/// page size.
+pub fn paginate_with_cursor<'a, T, Request>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a,
+    Request: 'a + Fn(Option<String>, u32) -> ClientResult<CursorBasedPage<T>>,
+{
+    let pages = CursorPageIterator {
+        req,
+        cursor: AfterCursor::default(),
+        page_size,
+    };
+
+    Box::new(pages.flat_map(|result| ResultIter::new(result.map(|page| page.items.into_iter()))))
+}
+
+impl<'a, T: 'a> Paginated<'a, T> for Paginator<'a, ClientResult<T>> {
+    fn all_items(self) -> ClientResult<Vec<T>> {
+        self.collect()
+    }
+
+    fn stream(self) -> Paginator<'a, ClientResult<T>> {
+        self
+    }
+}
+
+/// Iterator that repeatedly calls a function that returns a page until the last
 /// page is returned.
 struct PageIterator<Request> {
     req: Request,
-    offset: u32,
-    done: bool,
+    cursor: PageCursor,
     page_size: u32,
 }
 
@@ -39,20 +71,45 @@ where
     type Item = ClientResult<Page<T>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
-            return None;
+        let offset = self.cursor.next_offset()?;
+        match (self.req)(self.page_size, offset) {
+            Ok(page) => {
+                self.cursor.advance(&page);
+                Some(Ok(page))
+            }
+            Err(e) => {
+                self.cursor.finish();
+                Some(Err(e))
+            }
         }
+    }
+}
 
-        match (self.req)(self.page_size, self.offset) {
-            Ok(page) if page.items.is_empty() => {
-                self.done = true;
-                None
-            }
+/// Iterator that repeatedly calls a function that returns a cursor-based page
+/// until the last page is returned.
+struct CursorPageIterator<Request> {
+    req: Request,
+    cursor: AfterCursor,
+    page_size: u32,
+}
+
+impl<T, Request> Iterator for CursorPageIterator<Request>
+where
+    Request: Fn(Option<String>, u32) -> ClientResult<CursorBasedPage<T>>,
+{
+    type Item = ClientResult<CursorBasedPage<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let after = self.cursor.next_after()?;
+        match (self.req)(after, self.page_size) {
             Ok(page) => {
-                self.offset += page.items.len() as u32;
+                self.cursor.advance(&page);
                 Some(Ok(page))
             }
-            Err(e) => Some(Err(e)),
+            Err(e) => {
+                self.cursor.finish();
+                Some(Err(e))
+            }
         }
     }
 }
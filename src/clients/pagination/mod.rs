@@ -8,6 +8,15 @@
 //! * A `paginate` function, which returns a `Paginator` based on a request that
 //!   may be repeated in order to return a continuous sequence of `Page`s
 //!
+//! The pages are requested lazily: the next one is only fetched once all the
+//! items of the previous one have been consumed, so it's possible to use
+//! adaptors like `take(100)` without fetching the entire collection. The size
+//! of each page is configured with [`Config::pagination_chunks`], and the
+//! pagination ends once a page without a `next` URL is received. Both
+//! implementations share this logic with [`PageCursor`].
+//!
+//! [`Config::pagination_chunks`]: crate::Config::pagination_chunks
+//!
 //! Note that `Paginator` should actually be a trait so that a dynamic
 //! allocation can be avoided when returning it with `-> impl Iterator<T>`, as
 //! opposed to `-> Box<dyn Iterator<T>>`. But since the Spotify clients are
@@ -18,6 +27,22 @@
 //! Both `Paginator` and `paginate` have a lifetime of `'a`. This is because the
 //! pagination may borrow the client itself in order to make requests, and said
 //! lifetime helps ensure the `Paginator` struct won't outlive the client.
+//!
+//! The [`Paginated`] extension trait, exported in the
+//! [`prelude`](crate::prelude), provides the same methods for both
+//! implementations, so that it's not necessary to import the `futures` traits
+//! for the most common use cases:
+//!
+//! ```no_run
+//! # use rspotify::{prelude::*, model::ArtistId, ClientCredsSpotify, ClientResult};
+//! # #[maybe_async::maybe_async]
+//! # async fn example(spotify: &ClientCredsSpotify) -> ClientResult<()> {
+//! let artist = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+//! let albums = spotify.artist_albums(artist, None, None).all_items().await?;
+//! println!("{} albums", albums.len());
+//! # Ok(())
+//! # }
+//! ```
 
 #[cfg(feature = "__sync")]
 mod iter;
@@ -25,6 +50,90 @@ mod iter;
 mod stream;
 
 #[cfg(feature = "__sync")]
-pub use iter::{paginate, Paginator};
+pub use iter::{paginate, paginate_with_cursor, Paginator};
 #[cfg(feature = "__async")]
-pub use stream::{paginate, Paginator};
+pub use stream::{paginate, paginate_with_cursor, Paginator};
+
+use crate::{
+    model::{CursorBasedPage, Page},
+    ClientResult,
+};
+
+use maybe_async::maybe_async;
+
+/// Extension methods for the [`Paginator`] returned by the paginated
+/// endpoints.
+#[maybe_async(?Send)]
+pub trait Paginated<'a, T> {
+    /// Requests all the remaining pages and collects their items, stopping at
+    /// the first error.
+    async fn all_items(self) -> ClientResult<Vec<T>>;
+
+    /// Returns the items lazily, requesting the next page only once the
+    /// previous one has been consumed. This is the `Paginator` itself, so that
+    /// the usual adaptors of `Iterator` or `Stream` may be used.
+    fn stream(self) -> Paginator<'a, ClientResult<T>>;
+}
+
+/// Keeps track of the position in the paginated request.
+#[derive(Debug, Default)]
+pub(in crate) struct PageCursor {
+    offset: u32,
+    done: bool,
+}
+
+impl PageCursor {
+    /// The offset of the next page to be requested, or `None` if the last one
+    /// has been reached already.
+    pub(in crate) fn next_offset(&self) -> Option<u32> {
+        if self.done {
+            None
+        } else {
+            Some(self.offset)
+        }
+    }
+
+    /// Updates the position after a page has been received.
+    pub(in crate) fn advance<T>(&mut self, page: &Page<T>) {
+        self.offset += page.items.len() as u32;
+        self.done = page.next.is_none() || page.items.is_empty();
+    }
+
+    /// Stops the pagination, e.g. after an error.
+    #[cfg(feature = "__sync")]
+    pub(in crate) fn finish(&mut self) {
+        self.done = true;
+    }
+}
+
+/// Keeps track of the position in a cursor-based paginated request, which
+/// continues after the `after` cursor of the previous page.
+#[derive(Debug, Default)]
+pub(in crate) struct AfterCursor {
+    after: Option<String>,
+    done: bool,
+}
+
+impl AfterCursor {
+    /// The `after` cursor of the next page to be requested (`None` for the
+    /// first one), or `None` if the last one has been reached already.
+    pub(in crate) fn next_after(&mut self) -> Option<Option<String>> {
+        if self.done {
+            None
+        } else {
+            Some(self.after.take())
+        }
+    }
+
+    /// Updates the position after a page has been received.
+    pub(in crate) fn advance<T>(&mut self, page: &CursorBasedPage<T>) {
+        self.after = page.cursors.after.clone();
+        self.done = page.next.is_none() || page.items.is_empty() || self.after.is_none();
+    }
+
+    /// Stops the pagination, e.g. after an error.
+    #[cfg(feature = "__sync")]
+    pub(in crate) fn finish(&mut self) {
+        self.done = true;
+    }
+}
@@ -1,10 +1,17 @@
 //! Asynchronous implementation of automatic pagination requests.
 
-use crate::{model::Page, ClientResult};
+use super::{AfterCursor, PageCursor, Paginated};
+use crate::{
+    model::{CursorBasedPage, Page},
+    ClientResult,
+};
 
 use std::pin::Pin;
 
-use futures::{future::Future, stream::Stream};
+use futures::{
+    future::Future,
+    stream::{Stream, TryStreamExt},
+};
 
 /// Alias for `futures::stream::Stream<Item = T>`, since async mode is enabled.
 pub type Paginator<'a, T> = Pin<Box<dyn Stream<Item = T> + 'a>>;
@@ -20,17 +27,50 @@ where
     Request: Fn(u32, u32) -> Fut,
 {
     use async_stream::stream;
-    let mut offset = 0;
+    let mut cursor = PageCursor::default();
     Box::pin(stream! {
-        loop {
+        while let Some(offset) = cursor.next_offset() {
             let page = req(page_size, offset).await?;
-            offset += page.items.len() as u32;
+            cursor.advance(&page);
             for item in page.items {
                 yield Ok(item);
             }
-            if page.next.is_none() {
-                break;
+        }
+    })
+}
+
+/// This is used to handle cursor-based paginated requests automatically. The
+/// request receives the `after` cursor of the previous page, if any, and the
+/// page size.
+pub fn paginate_with_cursor<'a, T, Fut, Request>(
+    req: Request,
+    page_size: u32,
+) -> Paginator<'a, ClientResult<T>>
+where
+    T: 'a + Unpin,
+    Fut: Future<Output = ClientResult<CursorBasedPage<T>>>,
+    Request: 'a + Fn(Option<String>, u32) -> Fut,
+{
+    use async_stream::stream;
+    let mut cursor = AfterCursor::default();
+    Box::pin(stream! {
+        while let Some(after) = cursor.next_after() {
+            let page = req(after, page_size).await?;
+            cursor.advance(&page);
+            for item in page.items {
+                yield Ok(item);
             }
         }
     })
 }
+
+#[async_trait::async_trait(?Send)]
+impl<'a, T: 'a> Paginated<'a, T> for Paginator<'a, ClientResult<T>> {
+    async fn all_items(self) -> ClientResult<Vec<T>> {
+        self.try_collect().await
+    }
+
+    fn stream(self) -> Paginator<'a, ClientResult<T>> {
+        self
+    }
+}
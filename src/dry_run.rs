@@ -0,0 +1,195 @@
+//! Recording the requests instead of sending them, which is configured with
+//! [`Config::dry_run`].
+//!
+//! This is useful to test the code that uses Rspotify without a Spotify
+//! account or a mock server: the requests that would be sent can be inspected
+//! afterwards, and the responses are taken from the ones queued with
+//! [`DryRun::respond_with`].
+//!
+//! ```
+//! # use rspotify::{prelude::*, model::TrackId, ClientCredsSpotify, Config, DryRun, Token};
+//! # #[maybe_async::maybe_async]
+//! # async fn run() {
+//! let dry_run = DryRun::new();
+//! dry_run.respond_with(r#"{"tracks": []}"#);
+//! let config = Config {
+//!     dry_run: Some(dry_run.clone()),
+//!     ..Default::default()
+//! };
+//! let spotify = ClientCredsSpotify::from_token_with_config(
+//!     Token::default(),
+//!     Default::default(),
+//!     config,
+//! );
+//!
+//! let id = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+//! let tracks = spotify.tracks([id], None).await.unwrap();
+//! assert!(tracks.is_empty());
+//!
+//! let requests = dry_run.requests();
+//! assert_eq!(requests[0].method, "GET");
+//! assert_eq!(
+//!     requests[0].url,
+//!     "https://api.spotify.com/v1/tracks/?ids=4iV5W9uYEdYUVa79Axb7Rh"
+//! );
+//! # }
+//! # #[maybe_async::sync_impl]
+//! # fn main() {
+//! #     run()
+//! # }
+//! # #[maybe_async::async_impl]
+//! # #[tokio::main]
+//! # async fn main() {
+//! #     run().await
+//! # }
+//! ```
+//!
+//! [`Config::dry_run`]: crate::Config::dry_run
+
+use crate::http::{Form, Headers, Query};
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use serde_json::Value;
+use url::form_urlencoded;
+
+/// A request recorded by [`DryRun`] instead of being sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    /// The HTTP method, like `GET`.
+    pub method: String,
+    /// The full URL, including the query parameters.
+    pub url: String,
+    /// The headers set by Rspotify for this request, like the authorization
+    /// and the content type. The ones configured for every request, like the
+    /// user agent, aren't included.
+    pub headers: Headers,
+    /// The body of the request, which is empty for `GET` requests. Binary
+    /// payloads are converted lossily.
+    pub body: String,
+}
+
+/// Records the requests instead of sending them, and responds with the queued
+/// responses. It may be cloned to keep access to the recorded requests after
+/// passing it to the [`Config`](crate::Config).
+#[derive(Debug, Clone, Default)]
+pub struct DryRun(Arc<Mutex<DryRunState>>);
+
+#[derive(Debug, Default)]
+struct DryRunState {
+    requests: Vec<RecordedRequest>,
+    responses: VecDeque<String>,
+}
+
+impl DryRun {
+    pub fn new() -> Self {
+        DryRun::default()
+    }
+
+    /// Queues the body of the response to a request. The queued responses are
+    /// used in order, and once there are none left the body is empty.
+    pub fn respond_with(&self, body: impl Into<String>) {
+        self.0.lock().unwrap().responses.push_back(body.into());
+    }
+
+    /// The requests recorded so far, in the order they were made.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.0.lock().unwrap().requests.clone()
+    }
+
+    /// Records a request with query parameters, returning the next response.
+    pub(in crate) fn record_query(
+        &self,
+        method: &str,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Query,
+    ) -> String {
+        // Sorted so that the URL is deterministic
+        let mut params = payload.iter().collect::<Vec<_>>();
+        params.sort();
+        let mut url = url.to_owned();
+        if !params.is_empty() {
+            let query = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(params)
+                .finish();
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query);
+        }
+
+        self.record(
+            method,
+            url,
+            headers.cloned().unwrap_or_default(),
+            String::new(),
+        )
+    }
+
+    /// Records a request with a JSON body, returning the next response.
+    pub(in crate) fn record_json(
+        &self,
+        method: &str,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Value,
+    ) -> String {
+        self.record_bytes(
+            method,
+            url,
+            headers,
+            "application/json",
+            payload.to_string().as_bytes(),
+        )
+    }
+
+    /// Records a request with a form body, returning the next response.
+    pub(in crate) fn record_form(
+        &self,
+        method: &str,
+        url: &str,
+        headers: Option<&Headers>,
+        payload: &Form,
+    ) -> String {
+        let mut params = payload.iter().collect::<Vec<_>>();
+        params.sort();
+        let body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+        self.record_bytes(
+            method,
+            url,
+            headers,
+            "application/x-www-form-urlencoded",
+            body.as_bytes(),
+        )
+    }
+
+    /// Records a request with a binary body, returning the next response.
+    pub(in crate) fn record_bytes(
+        &self,
+        method: &str,
+        url: &str,
+        headers: Option<&Headers>,
+        content_type: &str,
+        payload: &[u8],
+    ) -> String {
+        let mut headers = headers.cloned().unwrap_or_default();
+        headers.insert("content-type".to_owned(), content_type.to_owned());
+        let body = String::from_utf8_lossy(payload).into_owned();
+        self.record(method, url.to_owned(), headers, body)
+    }
+
+    fn record(&self, method: &str, url: String, headers: Headers, body: String) -> String {
+        let mut state = self.0.lock().unwrap();
+        state.requests.push(RecordedRequest {
+            method: method.to_owned(),
+            url,
+            headers,
+            body,
+        });
+        state.responses.pop_front().unwrap_or_default()
+    }
+}
@@ -53,6 +53,18 @@
 //! environment variables `HTTP_PROXY` and `HTTPS_PROXY` environmental variables
 //! to set HTTP and HTTPS proxies, respectively.
 //!
+//! ### Timeouts and cancellation
+//!
+//! There's no timeout for the requests by default, but one can be configured
+//! with [`Config::request_timeout`]. It applies to each request individually,
+//! including the ones made to obtain or refresh the token, so a slow call
+//! fails with an error instead of hanging indefinitely.
+//!
+//! With the asynchronous clients, an in-flight request may also be cancelled
+//! at any time by dropping its future, e.g. when the user navigates away in a
+//! UI, or when using `tokio::time::timeout` or `tokio::select!`. The client
+//! can still be used normally afterwards.
+//!
 //! ### Environmental variables
 //!
 //! Rspotify supports the [`dotenv`] crate, which allows you to save credentials
@@ -70,6 +82,29 @@
 //! prompting the user, after enabling the `cli` feature. See the
 //! [Authorization](#authorization) section for more information.
 //!
+//! With the `cli-server` feature, the redirect URI doesn't have to be pasted
+//! by the user either: a local server listening on it obtains the code
+//! automatically, if the redirect URI is something like
+//! `http://localhost:8888/callback`.
+//!
+//! ### WebAssembly
+//!
+//! Rspotify can be compiled to `wasm32-unknown-unknown` for browser usage,
+//! where the [PKCE flow](#authorization) is the most appropriate. There's no
+//! filesystem, so the token is cached in memory with a
+//! [`MemoryCacheManager`] by default, and the file-based utilities aren't
+//! available. You'll also have to enable the `js` feature of `getrandom`,
+//! which is used to generate the random values of the authorization flows:
+//!
+//! ```toml
+//! [dependencies]
+//! rspotify = "..."
+//! getrandom = { version = "0.2", features = ["js"] }
+//! ```
+//!
+//! [`Config::request_timeout`] is ignored in the browser, whose fetch API
+//! doesn't support timeouts.
+//!
 //! ## Getting Started
 //!
 //! ### Authorization
@@ -123,8 +158,10 @@
 
 pub mod auth_code;
 pub mod auth_code_pkce;
+pub mod cache;
 pub mod client_creds;
 pub mod clients;
+pub mod dry_run;
 
 // Subcrate re-exports
 pub use rspotify_http as http;
@@ -133,32 +170,45 @@ pub use rspotify_model as model;
 // Top-level re-exports
 pub use auth_code::AuthCodeSpotify;
 pub use auth_code_pkce::AuthCodePkceSpotify;
+#[cfg(not(target_arch = "wasm32"))]
+pub use cache::FileCacheManager;
+pub use cache::{CacheManager, MemoryCacheManager};
 pub use client_creds::ClientCredsSpotify;
+pub use dry_run::{DryRun, RecordedRequest};
 pub use macros::scopes;
 
-use crate::http::HttpError;
+use crate::{
+    clients::LastResponse,
+    http::{Headers, HttpError, RequestOptions, ResponseHook},
+    model::{ApiError, Market, RecommendationsError},
+};
 
+use std::{collections::HashSet, env, fmt, path::PathBuf, sync::Arc};
+#[cfg(not(target_arch = "wasm32"))]
 use std::{
-    collections::HashSet,
-    env, fs,
+    fs,
     io::{Read, Write},
     path::Path,
-    path::PathBuf,
 };
 
+#[cfg(not(target_arch = "wasm32"))]
+use chrono::TimeZone;
 use chrono::{DateTime, Duration, Utc};
 use getrandom::getrandom;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 pub mod prelude {
-    pub use crate::clients::{BaseClient, OAuthClient};
+    pub use crate::clients::{pagination::Paginated, BaseClient, OAuthClient};
 }
 
 pub(in crate) mod headers {
     // Common headers as constants
     pub const CLIENT_ID: &str = "client_id";
     pub const CODE: &str = "code";
+    pub const CODE_CHALLENGE: &str = "code_challenge";
+    pub const CODE_CHALLENGE_METHOD: &str = "code_challenge_method";
+    pub const CODE_VERIFIER: &str = "code_verifier";
     pub const GRANT_AUTH_CODE: &str = "authorization_code";
     pub const GRANT_CLIENT_CREDS: &str = "client_credentials";
     pub const GRANT_REFRESH_TOKEN: &str = "refresh_token";
@@ -170,14 +220,6 @@ pub(in crate) mod headers {
     pub const SCOPE: &str = "scope";
     pub const SHOW_DIALOG: &str = "show_dialog";
     pub const STATE: &str = "state";
-    // TODO:
-    // pub const CODE_CHALLENGE: &str = "code_challenge";
-    // pub const CODE_CHALLENGE_METHOD: &str = "code_challenge_method";
-}
-
-pub(in crate) mod auth_urls {
-    pub const AUTHORIZE: &str = "https://accounts.spotify.com/authorize";
-    pub const TOKEN: &str = "https://accounts.spotify.com/api/token";
 }
 
 /// Possible errors returned from the `rspotify` client.
@@ -186,28 +228,96 @@ pub enum ClientError {
     #[error("json parse error: {0}")]
     ParseJson(#[from] serde_json::Error),
 
+    /// A response from Spotify couldn't be parsed into its model. `body` is
+    /// a truncated snippet of the response, with any tokens redacted.
+    #[error("json parse error: {source}, in response: {body}")]
+    ParseResponse {
+        #[source]
+        source: serde_json::Error,
+        body: String,
+    },
+
     #[error("url parse error: {0}")]
     ParseUrl(#[from] url::ParseError),
 
     #[error("http error: {0}")]
-    Http(#[from] HttpError),
+    Http(#[source] HttpError),
+
+    #[error("api error: {0}")]
+    Api(#[from] ApiError),
+
+    /// The request was rate limited. `retry_after` is the time to wait before
+    /// performing more requests, if Spotify included it in the response.
+    #[error("exceeded request limit")]
+    RateLimited { retry_after: Option<Duration> },
 
     #[error("input/output error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// The client isn't authorized to perform the request, e.g. because the
+    /// credentials are wrong or the token lacks the required scopes.
+    #[error("invalid authentication: {0}")]
+    InvalidAuth(String),
+
+    /// A parameter passed to an endpoint is invalid, so the request wasn't
+    /// performed. Unlike [`Self::InvalidAuth`], this is unrelated to the
+    /// credentials or the token, and retrying won't help unless the
+    /// parameters are fixed.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+
     #[cfg(feature = "cli")]
     #[error("cli error: {0}")]
     Cli(String),
 
     #[error("cache file error: {0}")]
     CacheFile(String),
+
+    /// A request failed while performing a batch of them, like
+    /// [`OAuthClient::current_user_saved_tracks_add_all`]. `index` is the
+    /// position of the failed chunk.
+    ///
+    /// [`OAuthClient::current_user_saved_tracks_add_all`]: crate::clients::OAuthClient::current_user_saved_tracks_add_all
+    #[error("request for chunk {index} failed: {source}")]
+    Chunk {
+        index: usize,
+        #[source]
+        source: Box<ClientError>,
+    },
+}
+
+// The error bodies returned by Spotify and the rate limit are detected by the
+// HTTP client, but they're exposed as separate variants so that they're easier
+// to match.
+impl From<HttpError> for ClientError {
+    fn from(err: HttpError) -> Self {
+        match err {
+            HttpError::Api(err) => ClientError::Api(err),
+            HttpError::RateLimited(retry_after) => ClientError::RateLimited {
+                retry_after: retry_after.map(|secs| Duration::seconds(secs as i64)),
+            },
+            err => ClientError::Http(err),
+        }
+    }
+}
+
+impl From<RecommendationsError> for ClientError {
+    fn from(err: RecommendationsError) -> Self {
+        ClientError::InvalidArgument(err.to_string())
+    }
 }
 
 pub type ClientResult<T> = Result<T, ClientError>;
 
 pub const DEFAULT_API_PREFIX: &str = "https://api.spotify.com/v1/";
+pub const DEFAULT_AUTH_BASE_URL: &str = "https://accounts.spotify.com/";
+pub const DEFAULT_TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
 pub const DEFAULT_CACHE_PATH: &str = ".spotify_token_cache.json";
 pub const DEFAULT_PAGINATION_CHUNKS: u32 = 50;
+pub const DEFAULT_TOKEN_EXPIRY_MARGIN_SECS: i64 = 10;
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+pub const DEFAULT_USER_AGENT: &str = concat!("rspotify/", env!("CARGO_PKG_VERSION"));
 
 /// Struct to configure the Spotify client.
 #[derive(Debug, Clone)]
@@ -215,10 +325,24 @@ pub struct Config {
     /// The Spotify API prefix, [`DEFAULT_API_PREFIX`] by default.
     pub prefix: String,
 
+    /// The base URL of the accounts service, to which the user is redirected
+    /// to authorize the app. Useful for testing against a mock server. By
+    /// default it's [`DEFAULT_AUTH_BASE_URL`].
+    pub auth_base_url: String,
+
+    /// The URL from which access tokens are requested. Useful for testing
+    /// against a mock server. By default it's [`DEFAULT_TOKEN_URL`].
+    pub token_url: String,
+
     /// The cache file path, in case it's used. By default it's
     /// [`DEFAULT_CACHE_PATH`]
     pub cache_path: PathBuf,
 
+    /// A custom backend for the token cache, in case it's used. By default
+    /// it's `None`, meaning that the token is saved into the file at
+    /// `cache_path` with a [`FileCacheManager`].
+    pub cache_manager: Option<Arc<dyn CacheManager>>,
+
     /// The pagination chunk size used when performing automatically paginated
     /// requests, like [`artist_albums`](crate::clients::BaseClient). This
     /// means that a request will be performed every `pagination_chunks` items.
@@ -228,24 +352,189 @@ pub struct Config {
     /// request, which most times is 50.
     pub pagination_chunks: u32,
 
+    /// Whether the token should be read from and saved into the cache, as
+    /// configured by `cache_manager`. It's disabled by default, in which case
+    /// the token is only kept in memory and the cache is never accessed.
     pub token_cached: bool,
 
     /// TODO
     pub token_refreshing: bool,
+
+    /// The margin of time before the token's expiration in which it's already
+    /// considered expired, so that it's renewed before it can expire in the
+    /// middle of a request. By default it's
+    /// [`DEFAULT_TOKEN_EXPIRY_MARGIN_SECS`] seconds.
+    pub token_expiry_margin: Duration,
+
+    /// Whether requests that fail because of the rate limit (HTTP 429) should
+    /// be retried automatically after waiting for the time indicated by
+    /// Spotify in the `Retry-After` header. If it's missing, an exponential
+    /// backoff is used instead. Disabled by default.
+    pub retry_on_rate_limit: bool,
+
+    /// Whether `GET` requests that fail because of a connection error, like a
+    /// DNS failure or a reset connection, should be retried automatically
+    /// with an exponential backoff. Other requests are never retried in that
+    /// case, since they may have been processed by Spotify already. Errors
+    /// with an HTTP status and timeouts aren't considered connection errors.
+    /// Disabled by default.
+    pub retry_on_transient: bool,
+
+    /// The maximum number of times a request is retried when
+    /// `retry_on_rate_limit` or `retry_on_transient` are enabled. By default
+    /// it's [`DEFAULT_MAX_RETRIES`].
+    pub max_retries: u32,
+
+    /// The maximum time a request may take before failing with a
+    /// [`ClientError::Http`]. It applies to each request separately, so it
+    /// doesn't limit the total time taken by paginated or chunked calls. By
+    /// default it's `None`, meaning that there's no timeout.
+    pub request_timeout: Option<std::time::Duration>,
+
+    /// A callback invoked with the status code and headers of the response to
+    /// every request made to Spotify, for logging or observability purposes.
+    /// If a request is retried, it's only invoked once, with the final
+    /// response. By default it's `None`.
+    pub on_response: Option<ResponseHook>,
+
+    /// The market used by the endpoints that accept one when it's not
+    /// specified in the call, which is useful for apps that serve a single
+    /// region. A market passed explicitly to an endpoint always takes
+    /// precedence. By default it's `None`.
+    pub default_market: Option<Market>,
+
+    /// Whether the body of every response should be logged at the debug
+    /// level before parsing it, which helps to diagnose deserialization
+    /// issues. Access and refresh tokens are redacted. Disabled by default.
+    pub log_raw_responses: bool,
+
+    /// The `User-Agent` header sent with every request, which may be
+    /// customized for analytics or to comply with proxies. By default it's
+    /// [`DEFAULT_USER_AGENT`]. If it's `None`, the default one of the HTTP
+    /// client is used, e.g. the one configured in the `reqwest::Client` given
+    /// to `with_http_client`.
+    pub user_agent: Option<String>,
+
+    /// Additional headers sent with every request, like the ones required by
+    /// some proxies. The headers set by Rspotify, like the authorization or
+    /// the user agent, take precedence, and the `Content-Type` header is
+    /// ignored. By default it's empty.
+    pub default_headers: Headers,
+
+    /// Records the requests instead of sending them, responding with the
+    /// queued responses, which is useful for testing. See [`DryRun`]. By
+    /// default it's `None`, meaning that the requests are sent to Spotify.
+    pub dry_run: Option<DryRun>,
+
+    /// A callback invoked with the new token after every successful refresh
+    /// of an [`AuthCodeSpotify`] or [`AuthCodePkceSpotify`] client, so that it
+    /// can be saved into external storage like a database. By default it's
+    /// `None`.
+    pub on_token_refresh: Option<TokenRefreshHook>,
+
+    /// The maximum number of requests performed at the same time by the
+    /// endpoints that run them concurrently, like
+    /// [`artists_albums`](crate::clients::BaseClient). Only used in async
+    /// mode. By default it's [`DEFAULT_MAX_CONCURRENT_REQUESTS`].
+    pub max_concurrent_requests: usize,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             prefix: String::from(DEFAULT_API_PREFIX),
+            auth_base_url: String::from(DEFAULT_AUTH_BASE_URL),
+            token_url: String::from(DEFAULT_TOKEN_URL),
             cache_path: PathBuf::from(DEFAULT_CACHE_PATH),
+            cache_manager: None,
             pagination_chunks: DEFAULT_PAGINATION_CHUNKS,
             token_cached: false,
             token_refreshing: false,
+            token_expiry_margin: Duration::seconds(DEFAULT_TOKEN_EXPIRY_MARGIN_SECS),
+            retry_on_rate_limit: false,
+            retry_on_transient: false,
+            max_retries: DEFAULT_MAX_RETRIES,
+            request_timeout: None,
+            on_response: None,
+            default_market: None,
+            log_raw_responses: false,
+            on_token_refresh: None,
+            user_agent: Some(DEFAULT_USER_AGENT.to_owned()),
+            default_headers: Headers::new(),
+            dry_run: None,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
         }
     }
 }
 
+/// A callback that receives the token obtained after a refresh.
+#[derive(Clone)]
+pub struct TokenRefreshHook(Arc<TokenRefreshHookFn>);
+
+type TokenRefreshHookFn = dyn Fn(&Token) + Send + Sync;
+
+impl TokenRefreshHook {
+    pub fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&Token) + Send + Sync + 'static,
+    {
+        TokenRefreshHook(Arc::new(hook))
+    }
+
+    pub fn call(&self, token: &Token) {
+        (self.0)(token)
+    }
+}
+
+impl fmt::Debug for TokenRefreshHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenRefreshHook")
+    }
+}
+
+impl Config {
+    /// Returns the cache manager used for the token, which is either the
+    /// custom one or a [`FileCacheManager`] at `cache_path`.
+    ///
+    /// On WebAssembly, where there's no filesystem, the default is a new
+    /// [`MemoryCacheManager`] instead, so nothing is actually cached unless a
+    /// custom one is configured.
+    pub fn get_cache_manager(&self) -> Arc<dyn CacheManager> {
+        match &self.cache_manager {
+            Some(manager) => Arc::clone(manager),
+            #[cfg(not(target_arch = "wasm32"))]
+            None => Arc::new(FileCacheManager::new(&self.cache_path)),
+            #[cfg(target_arch = "wasm32")]
+            None => Arc::new(MemoryCacheManager::default()),
+        }
+    }
+
+    /// The URL to which the user is redirected to authorize the app.
+    pub(in crate) fn authorize_url(&self) -> String {
+        format!("{}authorize", self.auth_base_url)
+    }
+
+    /// The settings to be passed to the HTTP client for each request. The
+    /// responses are kept in `last_response`, so that [`Self::on_response`]
+    /// is invoked just once after the retries.
+    pub(in crate) fn request_options(&self, last_response: &LastResponse) -> RequestOptions {
+        RequestOptions {
+            timeout: self.request_timeout,
+            on_response: self.on_response.as_ref().map(|_| last_response.hook()),
+            user_agent: self.user_agent.clone(),
+            default_headers: self.default_headers.clone(),
+        }
+    }
+
+    /// Returns the given market, or the default one if it's `None`.
+    pub(in crate) fn market_or_default<'a>(
+        &'a self,
+        market: Option<&'a Market>,
+    ) -> Option<&'a Market> {
+        market.or(self.default_market.as_ref())
+    }
+}
+
 /// Generate `length` random chars
 pub(in crate) fn generate_random_string(length: usize) -> String {
     let alphanum: &[u8] =
@@ -339,6 +628,7 @@ impl Default for Token {
 impl Token {
     /// Tries to initialize the token from a cache file.
     // TODO: maybe ClientResult for these things instead?
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn from_cache<T: AsRef<Path>>(path: T) -> Option<Self> {
         let mut file = fs::File::open(path).ok()?;
         let mut tok_str = String::new();
@@ -347,7 +637,47 @@ impl Token {
         serde_json::from_str::<Token>(&tok_str).ok()
     }
 
+    /// Reads the token from a cache file written by
+    /// [spotipy](https://github.com/plamere/spotipy), the Python library, to
+    /// ease the migration from it. Its expiration is saved as a Unix
+    /// timestamp, and its scopes as a `scope` field.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_spotipy_cache<T: AsRef<Path>>(path: T) -> ClientResult<Self> {
+        /// The layout of the token in spotipy's cache files.
+        #[derive(Deserialize)]
+        struct SpotipyToken {
+            access_token: String,
+            expires_in: i64,
+            expires_at: Option<i64>,
+            refresh_token: Option<String>,
+            scope: Option<String>,
+        }
+
+        let tok_str = fs::read_to_string(path)?;
+        let tok = serde_json::from_str::<SpotipyToken>(&tok_str)?;
+        let expires_at = match tok.expires_at {
+            Some(timestamp) => Some(Utc.timestamp_opt(timestamp, 0).single().ok_or_else(|| {
+                ClientError::CacheFile(format!("invalid expires_at: {}", timestamp))
+            })?),
+            None => None,
+        };
+
+        Ok(Token {
+            access_token: tok.access_token,
+            expires_in: Duration::seconds(tok.expires_in),
+            expires_at,
+            refresh_token: tok.refresh_token,
+            scopes: tok
+                .scope
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(ToOwned::to_owned)
+                .collect(),
+        })
+    }
+
     /// Saves the token information into its cache file.
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn write_cache<T: AsRef<Path>>(&self, path: T) -> ClientResult<()> {
         let token_info = serde_json::to_string(&self)?;
 
@@ -360,8 +690,34 @@ impl Token {
 
     /// Check if the token is expired
     pub fn is_expired(&self) -> bool {
+        self.is_expired_with_margin(Duration::zero())
+    }
+
+    /// Check if the token is expired or will expire within the given margin.
+    /// The clients use [`Config::token_expiry_margin`] for this, so that
+    /// tokens are considered expired slightly before they actually are.
+    pub fn is_expired_with_margin(&self, margin: Duration) -> bool {
+        self.expires_at
+            .map_or(true, |x| Utc::now().timestamp() > (x - margin).timestamp())
+    }
+
+    /// The number of seconds until the token expires, which is negative if it
+    /// has expired already. This may be used to show a countdown or to
+    /// schedule a refresh in advance. Returns `None` if the expiration time
+    /// is unknown.
+    pub fn expires_in_seconds(&self) -> Option<i64> {
         self.expires_at
-            .map_or(true, |x| Utc::now().timestamp() > x.timestamp())
+            .map(|x| x.timestamp() - Utc::now().timestamp())
+    }
+
+    /// Check if the token has been granted all the given scopes.
+    pub fn has_scopes(&self, required: &HashSet<String>) -> bool {
+        required.is_subset(&self.scopes)
+    }
+
+    /// Returns the given scopes that haven't been granted to the token.
+    pub fn missing_scopes(&self, required: &HashSet<String>) -> HashSet<String> {
+        required.difference(&self.scopes).cloned().collect()
     }
 }
 
@@ -395,6 +751,15 @@ impl Credentials {
             secret: env::var("RSPOTIFY_CLIENT_SECRET").ok()?,
         })
     }
+
+    /// The headers to authenticate with the client ID and secret, as required
+    /// by the token requests of the flows that have access to the secret.
+    pub fn auth_headers(&self) -> Headers {
+        let mut auth = Headers::new();
+        let (key, val) = clients::basic_auth(&self.id, &self.secret);
+        auth.insert(key, val);
+        auth
+    }
 }
 
 /// Structure that holds the required information for requests with OAuth.
@@ -424,12 +789,20 @@ impl OAuth {
     /// Parses the credentials from the environment variable
     /// `RSPOTIFY_REDIRECT_URI`. You can optionally activate the `env-file`
     /// feature in order to read these variables from a `.env` file.
-    pub fn from_env(scopes: HashSet<String>) -> Option<Self> {
+    ///
+    /// If the optional `RSPOTIFY_SCOPES` variable is set, its space-separated
+    /// scopes are added to the given ones, so that they can be adjusted
+    /// without recompiling.
+    pub fn from_env(mut scopes: HashSet<String>) -> Option<Self> {
         #[cfg(feature = "env-file")]
         {
             dotenv::dotenv().ok();
         }
 
+        if let Ok(extra) = env::var("RSPOTIFY_SCOPES") {
+            scopes.extend(extra.split_whitespace().map(ToOwned::to_owned));
+        }
+
         Some(OAuth {
             scopes,
             redirect_uri: env::var("RSPOTIFY_REDIRECT_URI").ok()?,
@@ -440,8 +813,8 @@ impl OAuth {
 
 #[cfg(test)]
 mod test {
-    use super::generate_random_string;
-    use std::collections::HashSet;
+    use super::{generate_random_string, scopes, OAuth};
+    use std::{collections::HashSet, env};
 
     #[test]
     fn test_generate_random_string() {
@@ -451,4 +824,30 @@ mod test {
         }
         assert_eq!(containers.len(), 100);
     }
+
+    #[test]
+    fn test_oauth_from_env_scopes() {
+        let vars = ["RSPOTIFY_REDIRECT_URI", "RSPOTIFY_SCOPES"];
+        let previous = vars.map(|key| (key, env::var(key).ok()));
+        env::set_var("RSPOTIFY_REDIRECT_URI", "http://localhost:8888/callback");
+        env::set_var("RSPOTIFY_SCOPES", "user-read-email  playlist-read-private");
+
+        let oauth = OAuth::from_env(scopes!("user-read-email", "user-top-read"));
+
+        // Restored before any assertion fails, so that other tests aren't
+        // affected
+        for (key, value) in previous {
+            match value {
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key),
+            }
+        }
+
+        let oauth = oauth.unwrap();
+        assert_eq!(oauth.redirect_uri, "http://localhost:8888/callback");
+        assert_eq!(
+            oauth.scopes,
+            scopes!("user-read-email", "user-top-read", "playlist-read-private")
+        );
+    }
 }
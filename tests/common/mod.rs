@@ -0,0 +1,255 @@
+//! Minimal HTTP server used to mock the Spotify API, so that the client's
+//! behaviour can be tested without any network access or credentials.
+//!
+//! Every accepted connection is answered with the next of the configured
+//! responses and then closed. The received requests are saved so that they
+//! can be inspected afterwards.
+
+#![allow(dead_code)]
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// A response to be sent by the [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// How long to wait before replying
+    pub delay: Option<Duration>,
+    /// Whether the connection is closed without replying
+    pub reset: bool,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: &str) -> Self {
+        MockResponse {
+            status,
+            headers: Vec::new(),
+            body: body.to_owned(),
+            delay: None,
+            reset: false,
+        }
+    }
+
+    /// Closes the connection without replying, like a network failure.
+    pub fn reset() -> Self {
+        MockResponse {
+            reset: true,
+            ..MockResponse::new(0, "")
+        }
+    }
+
+    pub fn header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+}
+
+/// A request received by the [`MockServer`].
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    /// The path, including the query
+    pub path: String,
+    /// The headers, with lowercase names
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockRequest {
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == &key.to_lowercase())
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+pub struct MockServer {
+    port: u16,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    max_concurrent: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+    /// Starts the server in a background thread, which will reply to the
+    /// incoming requests with the given responses, in order.
+    pub fn start(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = Arc::clone(&requests);
+        thread::spawn(move || {
+            for response in responses {
+                let (stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let request = read_request(&stream);
+                received.lock().unwrap().push(request);
+                write_response(stream, &response);
+            }
+        });
+
+        MockServer {
+            port,
+            requests,
+            max_concurrent: Arc::new(AtomicUsize::new(1)),
+        }
+    }
+
+    /// Starts the server in a background thread, which will reply to the
+    /// given number of requests concurrently, with the response built by
+    /// `handler` for each of them.
+    pub fn start_with<F>(count: usize, handler: F) -> Self
+    where
+        F: Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let max_concurrent = Arc::new(AtomicUsize::new(0));
+
+        let received = Arc::clone(&requests);
+        let max = Arc::clone(&max_concurrent);
+        let handler = Arc::new(handler);
+        thread::spawn(move || {
+            let in_flight = Arc::new(AtomicUsize::new(0));
+            for _ in 0..count {
+                let (stream, _) = match listener.accept() {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max.fetch_max(current, Ordering::SeqCst);
+
+                let received = Arc::clone(&received);
+                let in_flight = Arc::clone(&in_flight);
+                let handler = Arc::clone(&handler);
+                thread::spawn(move || {
+                    let request = read_request(&stream);
+                    let response = handler(&request);
+                    received.lock().unwrap().push(request);
+                    write_response(stream, &response);
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        MockServer {
+            port,
+            requests,
+            max_concurrent,
+        }
+    }
+
+    /// The URL to be used as the API prefix in the client's config.
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}/", self.port)
+    }
+
+    /// The requests received so far.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The maximum number of requests that were being handled at the same
+    /// time, which is always one unless started with [`Self::start_with`].
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent.load(Ordering::SeqCst)
+    }
+}
+
+fn read_request(stream: &TcpStream) -> MockRequest {
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let path = parts.next().unwrap_or_default().to_owned();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_lowercase(), value.trim().to_owned()));
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(key, _)| key == "content-length")
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).unwrap();
+
+    MockRequest {
+        method,
+        path,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    }
+}
+
+fn write_response(mut stream: TcpStream, response: &MockResponse) {
+    if let Some(delay) = response.delay {
+        thread::sleep(delay);
+    }
+    if response.reset {
+        return;
+    }
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.body.len()
+    );
+    for (key, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", key, value));
+    }
+    head.push_str("\r\n");
+
+    // The client may have given up already, e.g. after a timeout
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(response.body.as_bytes());
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        _ => "Unknown",
+    }
+}
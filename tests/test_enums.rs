@@ -10,6 +10,17 @@ fn test_include_external() {
 fn test_repeat_state() {
     let context = RepeatState::Context;
     assert_eq!(context.as_ref(), "context");
+
+    for (state, name) in [
+        (RepeatState::Off, "off"),
+        (RepeatState::Track, "track"),
+        (RepeatState::Context, "context"),
+    ] {
+        assert_eq!(state.as_ref(), name);
+        let json = format!("\"{}\"", name);
+        assert_eq!(serde_json::to_string(&state).unwrap(), json);
+        assert_eq!(serde_json::from_str::<RepeatState>(&json).unwrap(), state);
+    }
 }
 
 #[test]
@@ -70,4 +81,21 @@ fn test_search_type() {
 fn test_convert_country_from_str() {
     let zimbabwe = Country::Zimbabwe;
     assert_eq!(zimbabwe.as_ref(), "ZW");
+    assert_eq!("ZW".parse::<Country>().unwrap(), zimbabwe);
+}
+
+#[test]
+fn test_country_serde() {
+    for (country, code) in [
+        (Country::Spain, "ES"),
+        (Country::UnitedStates, "US"),
+        (Country::UnitedKingdom, "GB"),
+        (Country::Kosovo, "XK"),
+        (Country::Unknown("ZZ".to_owned()), "ZZ"),
+    ] {
+        assert_eq!(country.as_ref(), code);
+        let json = format!("\"{}\"", code);
+        assert_eq!(serde_json::to_string(&country).unwrap(), json);
+        assert_eq!(serde_json::from_str::<Country>(&json).unwrap(), country);
+    }
 }
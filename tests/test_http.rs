@@ -0,0 +1,2415 @@
+mod common;
+
+use chrono::{Duration, TimeZone, Utc};
+use common::{MockResponse, MockServer};
+use rspotify::{
+    http::{
+        BaseHttpClient, Form, Headers, HttpClient, HttpError, Query, RequestOptions, ResponseHook,
+    },
+    model::{
+        idtypes::Track, AdditionalType, AlbumId, ApiError, ArtistId, AudiobookId, Country,
+        CurrentlyPlayingType, EpisodeId, Market, Offset, PlayableId, PlayableItem, PlayableOffset,
+        PlaylistId, RecommendationsAttribute, RecommendationsAttributes, RecommendationsError,
+        RepeatState, SearchMultipleResult, SearchType, ShowId, TimeLimits, TimeRange, TrackId,
+        TrackPositions, UserId,
+    },
+    prelude::*,
+    scopes, AuthCodePkceSpotify, AuthCodeSpotify, ClientCredsSpotify, ClientError, Config,
+    Credentials, DryRun, OAuth, Token, TokenRefreshHook, DEFAULT_USER_AGENT,
+};
+
+fn client_with(server: &MockServer, config: Config) -> ClientCredsSpotify {
+    let config = Config {
+        prefix: server.url(),
+        ..config
+    };
+    let mut spotify = ClientCredsSpotify::with_config(Credentials::default(), config);
+    spotify.token = Some(Token {
+        access_token: "test-access-token".to_owned(),
+        ..Default::default()
+    });
+    spotify
+}
+
+/// A configuration that sends the authorization requests to the mock server.
+fn accounts_config(server: &MockServer) -> Config {
+    Config {
+        auth_base_url: server.url(),
+        token_url: format!("{}api/token", server.url()),
+        ..Default::default()
+    }
+}
+
+const TOKEN_RESPONSE: &str = r#"{
+    "access_token": "new-access-token",
+    "token_type": "Bearer",
+    "expires_in": 3600
+}"#;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pkce_flow() {
+    let server = MockServer::start(vec![MockResponse::new(200, TOKEN_RESPONSE); 2]);
+    let creds = Credentials::new("client-id", "");
+    let mut spotify =
+        AuthCodePkceSpotify::with_config(creds, OAuth::default(), accounts_config(&server));
+
+    let url = spotify.get_authorize_url(false).unwrap();
+    assert!(url.starts_with(&format!("{}authorize?", server.url())));
+    assert!(url.contains("code_challenge_method=S256"));
+    let verifier = spotify.verifier.clone().unwrap();
+
+    spotify.request_token("auth-code").await.unwrap();
+    assert_eq!(
+        spotify.token.as_ref().unwrap().access_token,
+        "new-access-token"
+    );
+    // The verifier is only used once
+    assert!(spotify.verifier.is_none());
+
+    // The verifier may also be given explicitly, e.g. after a restart
+    spotify
+        .request_token_with_verifier("other-code", "saved-verifier")
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].path, "/api/token");
+    assert!(requests[0].body.contains("grant_type=authorization_code"));
+    assert!(requests[0].body.contains("code=auth-code"));
+    assert!(requests[0]
+        .body
+        .contains(&format!("code_verifier={}", verifier)));
+    assert!(requests[1].body.contains("code=other-code"));
+    assert!(requests[1].body.contains("code_verifier=saved-verifier"));
+    for request in requests {
+        assert!(request.body.contains("client_id=client-id"));
+        assert_eq!(request.header("authorization"), None);
+    }
+}
+
+/// The authorization code flow authenticates its token requests with the
+/// client ID and secret, unlike PKCE.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_auth_code_token_auth() {
+    let server = MockServer::start(vec![MockResponse::new(200, TOKEN_RESPONSE); 2]);
+    let creds = Credentials::new("client-id", "client-secret");
+    let mut spotify =
+        AuthCodeSpotify::with_config(creds, OAuth::default(), accounts_config(&server));
+
+    spotify.request_token("auth-code").await.unwrap();
+    spotify.refresh_token("refresh-token").await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].body.contains("grant_type=authorization_code"));
+    assert!(requests[1].body.contains("grant_type=refresh_token"));
+    for request in requests {
+        assert_eq!(
+            request.header("authorization"),
+            Some("Basic Y2xpZW50LWlkOmNsaWVudC1zZWNyZXQ=")
+        );
+        assert!(!request.body.contains("client_id"));
+    }
+}
+
+/// PKCE clients don't have a secret, so the token is refreshed with the client
+/// ID in the body instead of the basic authorization.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pkce_refresh_token() {
+    let server = MockServer::start(vec![MockResponse::new(200, TOKEN_RESPONSE)]);
+    let creds = Credentials::new("client-id", "");
+    let mut spotify =
+        AuthCodePkceSpotify::with_config(creds, OAuth::default(), accounts_config(&server));
+
+    spotify.refresh_token("refresh-token").await.unwrap();
+    assert_eq!(
+        spotify.token.as_ref().unwrap().access_token,
+        "new-access-token"
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/api/token");
+    assert!(requests[0].body.contains("grant_type=refresh_token"));
+    assert!(requests[0].body.contains("refresh_token=refresh-token"));
+    assert!(requests[0].body.contains("client_id=client-id"));
+    assert_eq!(requests[0].header("authorization"), None);
+}
+
+const ROTATED_TOKEN_RESPONSE: &str = r#"{
+    "access_token": "rotated-access-token",
+    "token_type": "Bearer",
+    "expires_in": 3600,
+    "refresh_token": "rotated-refresh-token"
+}"#;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_refresh_token_rotation() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, ROTATED_TOKEN_RESPONSE),
+        MockResponse::new(200, TOKEN_RESPONSE),
+    ]);
+    let mut spotify = AuthCodeSpotify::with_config(
+        Credentials::default(),
+        OAuth::default(),
+        accounts_config(&server),
+    );
+
+    // A new refresh token replaces the previous one
+    spotify.refresh_token("old-refresh-token").await.unwrap();
+    let token = spotify.token.as_ref().unwrap();
+    assert_eq!(token.access_token, "rotated-access-token");
+    assert_eq!(
+        token.refresh_token.as_deref(),
+        Some("rotated-refresh-token")
+    );
+
+    // Otherwise, the previous one is kept
+    spotify
+        .refresh_token("rotated-refresh-token")
+        .await
+        .unwrap();
+    let token = spotify.token.as_ref().unwrap();
+    assert_eq!(token.access_token, "new-access-token");
+    assert_eq!(
+        token.refresh_token.as_deref(),
+        Some("rotated-refresh-token")
+    );
+
+    let requests = server.requests();
+    assert!(requests[0].body.contains("grant_type=refresh_token"));
+    assert!(requests[0].body.contains("refresh_token=old-refresh-token"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pkce_refresh_token_rotation() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, ROTATED_TOKEN_RESPONSE),
+        MockResponse::new(200, TOKEN_RESPONSE),
+    ]);
+    let mut spotify = AuthCodePkceSpotify::with_config(
+        Credentials::new("client-id", ""),
+        OAuth::default(),
+        accounts_config(&server),
+    );
+
+    // A new refresh token replaces the previous one
+    spotify.refresh_token("old-refresh-token").await.unwrap();
+    let token = spotify.token.as_ref().unwrap();
+    assert_eq!(token.access_token, "rotated-access-token");
+    assert_eq!(
+        token.refresh_token.as_deref(),
+        Some("rotated-refresh-token")
+    );
+
+    // Otherwise, the previous one is kept
+    spotify
+        .refresh_token("rotated-refresh-token")
+        .await
+        .unwrap();
+    let token = spotify.token.as_ref().unwrap();
+    assert_eq!(token.access_token, "new-access-token");
+    assert_eq!(
+        token.refresh_token.as_deref(),
+        Some("rotated-refresh-token")
+    );
+
+    let requests = server.requests();
+    assert!(requests[0].body.contains("refresh_token=old-refresh-token"));
+    assert!(requests[1]
+        .body
+        .contains("refresh_token=rotated-refresh-token"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_token_endpoint_error() {
+    let server = MockServer::start(vec![MockResponse::new(
+        400,
+        r#"{"error": "invalid_client", "error_description": "Invalid client secret"}"#,
+    )]);
+    let mut spotify =
+        ClientCredsSpotify::with_config(Credentials::default(), accounts_config(&server));
+
+    let err = spotify.request_token().await.unwrap_err();
+    match err {
+        ClientError::InvalidAuth(msg) => assert_eq!(msg, "invalid_client: Invalid client secret"),
+        err => panic!("unexpected error: {:?}", err),
+    }
+    assert!(spotify.token.is_none());
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_get_raw() {
+    let server = MockServer::start(vec![MockResponse::new(
+        200,
+        r#"{"items": [], "new_field": true}"#,
+    )]);
+    let spotify = client_with(&server, Config::default());
+
+    let response = spotify
+        .get_raw("me/top/artists", &[("time_range", "short_term")])
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        serde_json::json!({"items": [], "new_field": true})
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "/me/top/artists?time_range=short_term");
+    assert_eq!(
+        requests[0].header("authorization"),
+        Some("Bearer test-access-token")
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_retry_on_rate_limit() {
+    let server = MockServer::start(vec![
+        MockResponse::new(429, "").header("Retry-After", "0"),
+        MockResponse::new(200, r#"{"ok": true}"#),
+    ]);
+    let config = Config {
+        retry_on_rate_limit: true,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    let response = spotify.get("me", None, &Query::new()).await.unwrap();
+    assert_eq!(response, r#"{"ok": true}"#);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_retry_on_transient() {
+    let server = MockServer::start(vec![
+        MockResponse::reset(),
+        MockResponse::new(200, r#"{"ok": true}"#),
+        MockResponse::reset(),
+    ]);
+    let config = Config {
+        retry_on_transient: true,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    let response = spotify.get("me", None, &Query::new()).await.unwrap();
+    assert_eq!(response, r#"{"ok": true}"#);
+    assert_eq!(server.requests().len(), 2);
+
+    // Only GET requests are retried
+    let err = spotify
+        .put("me/player/pause", None, &serde_json::json!({}))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Http(HttpError::Connection(_))));
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_rate_limit_without_retries() {
+    let server = MockServer::start(vec![
+        MockResponse::new(429, "").header("Retry-After", "0"),
+        MockResponse::new(200, r#"{"ok": true}"#),
+    ]);
+    let spotify = client_with(&server, Config::default());
+
+    let err = spotify.get("me", None, &Query::new()).await.unwrap_err();
+    assert!(matches!(
+        err,
+        ClientError::RateLimited {
+            retry_after: Some(d)
+        } if d == Duration::zero()
+    ));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_rate_limit_retry_after() {
+    let server = MockServer::start(vec![MockResponse::new(429, "").header("Retry-After", "3")]);
+    let spotify = client_with(&server, Config::default());
+
+    let err = spotify.get("me", None, &Query::new()).await.unwrap_err();
+    match err {
+        ClientError::RateLimited { retry_after } => {
+            assert_eq!(retry_after, Some(Duration::seconds(3)))
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_max_retries() {
+    let server = MockServer::start(vec![
+        MockResponse::new(429, "").header("Retry-After", "0"),
+        MockResponse::new(429, "").header("Retry-After", "0"),
+        MockResponse::new(200, r#"{"ok": true}"#),
+    ]);
+    let config = Config {
+        retry_on_rate_limit: true,
+        max_retries: 1,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    let err = spotify.get("me", None, &Query::new()).await.unwrap_err();
+    assert!(matches!(err, ClientError::RateLimited { .. }));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_api_error() {
+    let server = MockServer::start(vec![
+        MockResponse::new(
+            404,
+            r#"{"error": {"status": 404, "message": "Non existing id"}}"#,
+        ),
+        MockResponse::new(500, "Something went wrong"),
+    ]);
+    let spotify = client_with(&server, Config::default());
+
+    let err = spotify
+        .get("tracks/abc", None, &Query::new())
+        .await
+        .unwrap_err();
+    match err {
+        ClientError::Api(ApiError::Regular { status, message }) => {
+            assert_eq!(status, 404);
+            assert_eq!(message, "Non existing id");
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+
+    // Bodies that can't be parsed are still reported as regular HTTP errors
+    let err = spotify
+        .get("tracks/abc", None, &Query::new())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ClientError::Http(HttpError::StatusCode(500, _))
+    ));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_auth_error() {
+    let server = MockServer::start(vec![MockResponse::new(
+        400,
+        r#"{"error": "invalid_grant", "error_description": "Invalid authorization code"}"#,
+    )]);
+    let spotify = client_with(&server, Config::default());
+
+    let err = spotify
+        .post_form("api/token", None, &Form::new())
+        .await
+        .unwrap_err();
+    match err {
+        ClientError::Api(ApiError::Auth {
+            error,
+            error_description,
+        }) => {
+            assert_eq!(error, "invalid_grant");
+            assert_eq!(error_description, "Invalid authorization code");
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+fn albums_page(names: &[&str], next: Option<&str>) -> MockResponse {
+    let items = names
+        .iter()
+        .map(|name| {
+            serde_json::json!({
+                "album_type": "album",
+                "artists": [],
+                "external_urls": {},
+                "href": null,
+                "id": null,
+                "images": [],
+                "name": name,
+                "type": "album",
+                "uri": null
+            })
+        })
+        .collect::<Vec<_>>();
+    let page = serde_json::json!({
+        "href": "",
+        "items": items,
+        "limit": 2,
+        "next": next,
+        "offset": 0,
+        "previous": null,
+        "total": 3
+    });
+    MockResponse::new(200, &page.to_string())
+}
+
+/// The paginated endpoints return a stream that only requests the next page
+/// once the previous one has been consumed.
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_pagination_stream_is_lazy() {
+    use futures_util::{StreamExt, TryStreamExt};
+    use rspotify::model::ArtistId;
+
+    let server = MockServer::start(vec![
+        albums_page(&["first", "second"], Some("next")),
+        albums_page(&["third"], None),
+    ]);
+    let config = Config {
+        pagination_chunks: 2,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+    let artist = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+
+    let first = spotify
+        .artist_albums(artist, None, None)
+        .take(1)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(first.len(), 1);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].path.contains("limit=2"));
+    assert!(requests[0].path.contains("offset=0"));
+}
+
+/// The stream stops once there's no next page.
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_pagination_stream_stops() {
+    use futures_util::TryStreamExt;
+    use rspotify::model::ArtistId;
+
+    let server = MockServer::start(vec![
+        albums_page(&["first", "second"], Some("next")),
+        albums_page(&["third"], None),
+    ]);
+    let config = Config {
+        pagination_chunks: 2,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+    let artist = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+
+    let names = spotify
+        .artist_albums(artist, None, None)
+        .map_ok(|album| album.name)
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+    assert_eq!(names, ["first", "second", "third"]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].path.contains("offset=2"));
+}
+
+/// The iterator only requests the next page once the previous one has been
+/// consumed, and stops once there's no next page.
+#[cfg(feature = "__sync")]
+#[test]
+fn test_pagination_iter() {
+    use rspotify::model::ArtistId;
+
+    let first_names = (0..50).map(|i| i.to_string()).collect::<Vec<_>>();
+    let first_names = first_names.iter().map(String::as_str).collect::<Vec<_>>();
+    let server = MockServer::start(vec![
+        albums_page(&first_names, Some("next")),
+        albums_page(&["50"], None),
+    ]);
+    let spotify = client_with(&server, Config::default());
+    let artist = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+
+    let mut albums = spotify.artist_albums(artist, None, None);
+    let first = albums.next().unwrap().unwrap();
+    assert_eq!(first.name, "0");
+    assert_eq!(server.requests().len(), 1);
+
+    let rest = albums.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(rest.len(), 50);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].path.contains("limit=50"));
+    assert!(requests[1].path.contains("offset=50"));
+}
+
+/// `all_items` collects the items of every page.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pagination_all_items() {
+    let server = MockServer::start(vec![
+        albums_page(&["first", "second"], Some("next")),
+        albums_page(&["third"], None),
+    ]);
+    let config = Config {
+        pagination_chunks: 2,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+    let artist = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+
+    let albums = spotify
+        .artist_albums(artist, None, None)
+        .all_items()
+        .await
+        .unwrap();
+    let names = albums.iter().map(|a| a.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["first", "second", "third"]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].path.contains("offset=0"));
+    assert!(requests[1].path.contains("offset=2"));
+}
+
+/// The given `reqwest::Client` is used to perform the requests, including its
+/// configuration.
+#[cfg(feature = "client-reqwest")]
+#[tokio::test]
+async fn test_with_http_client() {
+    let server = MockServer::start(vec![MockResponse::new(200, r#"{"ok": true}"#)]);
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-custom-header", "custom".parse().unwrap());
+    let client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+    let config = Config {
+        prefix: server.url(),
+        ..Default::default()
+    };
+    let spotify = ClientCredsSpotify::with_http_client(Credentials::default(), config, client);
+
+    spotify.get("me", None, &Query::new()).await.unwrap();
+    let requests = server.requests();
+    assert_eq!(requests[0].header("x-custom-header"), Some("custom"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_request_timeout() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, r#"{"ok": true}"#).delay(std::time::Duration::from_secs(2))
+    ]);
+    let config = Config {
+        request_timeout: Some(std::time::Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    let err = spotify.get("me", None, &Query::new()).await.unwrap_err();
+    assert!(matches!(err, ClientError::Http(HttpError::Request(_))));
+}
+
+/// Dropping the future of a request cancels it, and the client can still be
+/// used afterwards.
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_request_cancellation() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, r#"{"ok": true}"#).delay(std::time::Duration::from_secs(2)),
+        MockResponse::new(200, r#"{"ok": true}"#),
+    ]);
+    let spotify = client_with(&server, Config::default());
+
+    let start = std::time::Instant::now();
+    let res = tokio::time::timeout(
+        std::time::Duration::from_millis(100),
+        spotify.get("me", None, &Query::new()),
+    )
+    .await;
+    assert!(res.is_err());
+    assert!(start.elapsed() < std::time::Duration::from_secs(1));
+
+    let body = spotify.get("me", None, &Query::new()).await.unwrap();
+    assert_eq!(body, r#"{"ok": true}"#);
+}
+
+fn auth_code_client_with(server: &MockServer) -> AuthCodeSpotify {
+    let config = Config {
+        prefix: server.url(),
+        ..Default::default()
+    };
+    let mut spotify =
+        AuthCodeSpotify::with_config(Credentials::default(), OAuth::default(), config);
+    spotify.token = Some(Token {
+        access_token: "test-access-token".to_owned(),
+        ..Default::default()
+    });
+    spotify
+}
+
+fn track_ids(n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("{:0>22}", i)).collect()
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_saved_tracks_add_all() {
+    let server = MockServer::start(vec![MockResponse::new(200, ""); 3]);
+    let spotify = auth_code_client_with(&server);
+
+    let ids = track_ids(120);
+    let ids = ids.iter().map(|id| TrackId::from_id(id).unwrap());
+    spotify
+        .current_user_saved_tracks_add_all(ids)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    let sizes = requests
+        .iter()
+        .map(|req| req.path.split(',').count())
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, [50, 50, 20]);
+    assert!(requests.iter().all(|req| req.method == "PUT"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_saved_tracks_add_all_error() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, ""),
+        MockResponse::new(500, ""),
+        MockResponse::new(200, ""),
+    ]);
+    let spotify = auth_code_client_with(&server);
+
+    let ids = track_ids(120);
+    let ids = ids.iter().map(|id| TrackId::from_id(id).unwrap());
+    let err = spotify
+        .current_user_saved_tracks_add_all(ids)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, ClientError::Chunk { index: 1, .. }));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_on_response() {
+    use std::sync::{Arc, Mutex};
+
+    let server = MockServer::start(vec![
+        MockResponse::new(429, "").header("Retry-After", "0"),
+        MockResponse::new(200, r#"{"ok": true}"#).header("X-Custom", "value"),
+    ]);
+    let responses = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&responses);
+    let config = Config {
+        retry_on_rate_limit: true,
+        on_response: Some(ResponseHook::new(move |status, headers| {
+            received
+                .lock()
+                .unwrap()
+                .push((status, headers.get("x-custom").cloned()));
+        })),
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    spotify.get("me", None, &Query::new()).await.unwrap();
+    // Only once, with the final response after the retry
+    assert_eq!(
+        *responses.lock().unwrap(),
+        [(200, Some("value".to_owned()))]
+    );
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_on_response_without_final_response() {
+    use std::sync::{Arc, Mutex};
+
+    // The server is gone after the first response, so the retry fails with a
+    // connection error
+    let server = MockServer::start(vec![MockResponse::new(429, "").header("Retry-After", "0")]);
+    let responses = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&responses);
+    let config = Config {
+        retry_on_rate_limit: true,
+        on_response: Some(ResponseHook::new(move |status, _| {
+            received.lock().unwrap().push(status);
+        })),
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    let err = spotify.get("me", None, &Query::new()).await.unwrap_err();
+    assert!(matches!(err, ClientError::Http(_)));
+    // The rate limited response isn't reported as the final one
+    assert!(responses.lock().unwrap().is_empty());
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_user_agent() {
+    let server = MockServer::start(vec![MockResponse::new(200, r#"{"ok": true}"#); 3]);
+    let mut spotify = client_with(&server, Config::default());
+
+    spotify.get("me", None, &Query::new()).await.unwrap();
+    spotify.config.user_agent = Some("my-app/1.0".to_owned());
+    spotify.get("me", None, &Query::new()).await.unwrap();
+    spotify.config.user_agent = None;
+    spotify.get("me", None, &Query::new()).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].header("user-agent"), Some(DEFAULT_USER_AGENT));
+    assert!(DEFAULT_USER_AGENT.starts_with("rspotify/"));
+    assert_eq!(requests[1].header("user-agent"), Some("my-app/1.0"));
+    // The HTTP client's default, if any
+    assert_ne!(requests[2].header("user-agent"), Some(DEFAULT_USER_AGENT));
+}
+
+/// The default headers are sent with every request, but the ones set by
+/// Rspotify take precedence.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_default_headers() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, ""),
+        MockResponse::new(200, "[true]"),
+    ]);
+    let mut spotify = auth_code_client_with(&server);
+    for (key, val) in [
+        ("X-Tenant-Id", "tenant"),
+        ("Authorization", "Bearer other-token"),
+        ("Content-Type", "text/plain"),
+    ] {
+        spotify
+            .config
+            .default_headers
+            .insert(key.to_owned(), val.to_owned());
+    }
+
+    let album = AlbumId::from_id("6akEvsycLGftJxYudPjmqK").unwrap();
+    spotify
+        .current_user_saved_albums_add([album])
+        .await
+        .unwrap();
+    spotify
+        .current_user_saved_albums_contains([album])
+        .await
+        .unwrap();
+
+    for request in server.requests() {
+        assert_eq!(request.header("x-tenant-id"), Some("tenant"));
+        assert_eq!(
+            request.header("authorization"),
+            Some("Bearer test-access-token")
+        );
+    }
+    let requests = server.requests();
+    assert_eq!(requests[0].header("content-type"), Some("application/json"));
+    assert_eq!(requests[1].header("content-type"), None);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_dry_run() {
+    let server = MockServer::start(vec![]);
+    let dry_run = DryRun::new();
+    dry_run.respond_with("[true]");
+    let mut spotify = auth_code_client_with(&server);
+    spotify.config.dry_run = Some(dry_run.clone());
+
+    let album = AlbumId::from_id("6akEvsycLGftJxYudPjmqK").unwrap();
+    let contains = spotify
+        .current_user_saved_albums_contains([album])
+        .await
+        .unwrap();
+    assert_eq!(contains, vec![true]);
+    spotify
+        .current_user_saved_albums_add([album])
+        .await
+        .unwrap();
+
+    assert!(server.requests().is_empty());
+    let requests = dry_run.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(
+        requests[0].url,
+        format!(
+            "{}me/albums/contains/?ids=6akEvsycLGftJxYudPjmqK",
+            server.url()
+        )
+    );
+    assert_eq!(
+        requests[0].headers.get("authorization").map(String::as_str),
+        Some("Bearer test-access-token")
+    );
+    assert_eq!(requests[0].body, "");
+    assert_eq!(requests[1].method, "PUT");
+    assert_eq!(
+        requests[1].url,
+        format!("{}me/albums/?ids=6akEvsycLGftJxYudPjmqK", server.url())
+    );
+    assert_eq!(
+        requests[1].headers.get("content-type").map(String::as_str),
+        Some("application/json")
+    );
+    assert_eq!(requests[1].body, "{}");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_missing_scopes() {
+    let server = MockServer::start(vec![]);
+    let mut spotify = auth_code_client_with(&server);
+    spotify.token.as_mut().unwrap().scopes = scopes!("user-library-read");
+
+    let ids = track_ids(1);
+    let ids = ids.iter().map(|id| TrackId::from_id(id).unwrap());
+    let err = spotify
+        .current_user_saved_tracks_add(ids)
+        .await
+        .unwrap_err();
+
+    match err {
+        ClientError::InvalidAuth(msg) => assert!(msg.contains("user-library-modify")),
+        err => panic!("unexpected error: {:?}", err),
+    }
+    // The request isn't even performed
+    assert!(server.requests().is_empty());
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_search_multiple() {
+    let server = MockServer::start(vec![MockResponse::new(200, "{}")]);
+    let spotify = client_with(&server, Config::default());
+
+    let result = spotify
+        .search_multiple(
+            "rust",
+            &[SearchType::Show, SearchType::Episode],
+            None,
+            None,
+            Some(1),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(result, SearchMultipleResult::default());
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].path.contains("type=show%2Cepisode"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_transfer_playback() {
+    let server = MockServer::start(vec![MockResponse::new(204, ""); 3]);
+    let spotify = auth_code_client_with(&server);
+
+    spotify
+        .transfer_playback("device", Some(true))
+        .await
+        .unwrap();
+    spotify
+        .transfer_playback("device", Some(false))
+        .await
+        .unwrap();
+    spotify.transfer_playback("device", None).await.unwrap();
+
+    let bodies = server
+        .requests()
+        .into_iter()
+        .map(|req| {
+            assert_eq!(req.method, "PUT");
+            assert_eq!(req.path, "/me/player");
+            serde_json::from_str::<serde_json::Value>(&req.body).unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bodies,
+        vec![
+            serde_json::json!({"device_ids": ["device"], "play": true}),
+            serde_json::json!({"device_ids": ["device"], "play": false}),
+            serde_json::json!({"device_ids": ["device"]}),
+        ]
+    );
+}
+
+/// A full episode object, as returned by Spotify
+const EPISODE: &str = r#"
+{
+  "audio_preview_url": null,
+  "description": "Talking about async Rust",
+  "duration_ms": 2685023,
+  "explicit": false,
+  "external_urls": {
+    "spotify": "https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ"
+  },
+  "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+  "id": "512ojhOuo1ktJprKbVcKyQ",
+  "images": [],
+  "is_externally_hosted": false,
+  "is_playable": true,
+  "language": "en",
+  "languages": ["en"],
+  "name": "Async Rust",
+  "release_date": "2021-03-01",
+  "release_date_precision": "day",
+  "resume_point": null,
+  "show": {
+    "available_markets": ["US"],
+    "copyrights": [],
+    "description": "A podcast about the Rust programming language",
+    "explicit": false,
+    "external_urls": {
+      "spotify": "https://open.spotify.com/show/4rOoJ6Egrf8K2IrywzwOMk"
+    },
+    "href": "https://api.spotify.com/v1/shows/4rOoJ6Egrf8K2IrywzwOMk",
+    "id": "4rOoJ6Egrf8K2IrywzwOMk",
+    "images": [],
+    "is_externally_hosted": false,
+    "languages": ["en"],
+    "media_type": "audio",
+    "name": "Rustacean Station",
+    "publisher": "Rustacean Station",
+    "type": "show",
+    "uri": "spotify:show:4rOoJ6Egrf8K2IrywzwOMk"
+  },
+  "type": "episode",
+  "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+}
+"#;
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_currently_playing_episode() {
+    let body = format!(
+        r#"
+{{
+  "context": null,
+  "timestamp": 1607769168429,
+  "progress_ms": 2259,
+  "is_playing": true,
+  "item": {},
+  "currently_playing_type": "episode",
+  "actions": {{
+    "disallows": {{
+      "resuming": true
+    }}
+  }}
+}}
+"#,
+        EPISODE
+    );
+    let server = MockServer::start(vec![MockResponse::new(200, &body)]);
+    let spotify = auth_code_client_with(&server);
+
+    let context = spotify
+        .current_playing(None, Some(&[AdditionalType::Episode]))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        context.currently_playing_type,
+        CurrentlyPlayingType::Episode
+    );
+    match context.item {
+        Some(PlayableItem::Episode(episode)) => assert_eq!(episode.name, "Async Rust"),
+        item => panic!("unexpected item: {:?}", item),
+    }
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].path,
+        "/me/player/currently-playing?additional_types=episode"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_default_market() {
+    let server = MockServer::start(vec![MockResponse::new(200, r#"{"tracks": []}"#); 2]);
+    let config = Config {
+        default_market: Some(Market::Country(Country::Spain)),
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+    let ids = [TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap()];
+
+    spotify.tracks(ids, None).await.unwrap();
+    spotify.tracks(ids, Some(&Market::FromToken)).await.unwrap();
+
+    let requests = server.requests();
+    assert!(requests[0].path.ends_with("market=ES"));
+    assert!(requests[1].path.ends_with("market=from_token"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_several_items_missing() {
+    let artist = r#"{
+        "external_urls": {},
+        "followers": {"href": null, "total": 10},
+        "genres": [],
+        "href": "https://api.spotify.com/v1/artists/0OdUWJ0sBjDrqHygGUXeCF",
+        "id": "0OdUWJ0sBjDrqHygGUXeCF",
+        "images": [],
+        "name": "Band of Horses",
+        "popularity": 59,
+        "type": "artist",
+        "uri": "spotify:artist:0OdUWJ0sBjDrqHygGUXeCF"
+    }"#;
+    let server = MockServer::start(vec![
+        MockResponse::new(200, &format!(r#"{{"artists": [null, {}]}}"#, artist)),
+        MockResponse::new(200, r#"{"tracks": [null]}"#),
+        MockResponse::new(200, r#"{"albums": [null]}"#),
+    ]);
+    let spotify = client_with(&server, Config::default());
+
+    let ids = [
+        ArtistId::from_id("0000000000000000000000").unwrap(),
+        ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap(),
+    ];
+    let artists = spotify.artists(ids).await.unwrap();
+    assert_eq!(artists.len(), 2);
+    assert!(artists[0].is_none());
+    assert_eq!(artists[1].as_ref().unwrap().name, "Band of Horses");
+
+    let ids = [TrackId::from_id("0000000000000000000000").unwrap()];
+    let tracks = spotify.tracks(ids, None).await.unwrap();
+    assert_eq!(tracks, [None]);
+
+    let ids = [AlbumId::from_id("0000000000000000000000").unwrap()];
+    let albums = spotify.albums(ids).await.unwrap();
+    assert_eq!(albums, [None]);
+}
+
+fn simplified_track_json(id: Option<&str>) -> serde_json::Value {
+    serde_json::json!({
+        "artists": [],
+        "available_markets": null,
+        "disc_number": 1,
+        "duration_ms": 1000,
+        "explicit": false,
+        "external_urls": {},
+        "href": null,
+        "id": id,
+        "is_local": id.is_none(),
+        "is_playable": null,
+        "linked_from": null,
+        "restrictions": null,
+        "name": "Cut To The Feeling",
+        "preview_url": null,
+        "track_number": 1,
+        "type": "track",
+        "uri": "spotify:track:11dFghVXANMlKmJXsNCbNl"
+    })
+}
+
+fn full_track_json(id: &str, available_markets: &[&str]) -> serde_json::Value {
+    let mut track = simplified_track_json(Some(id));
+    track["album"] = serde_json::json!({
+        "album_type": "single",
+        "artists": [],
+        "external_urls": {},
+        "href": null,
+        "id": "0tGPJ0bkWOUmH7MEOR77qc",
+        "images": [],
+        "name": "Cut To The Feeling",
+        "type": "album",
+        "uri": null
+    });
+    track["available_markets"] = serde_json::json!(available_markets);
+    track["external_ids"] = serde_json::json!({});
+    track["popularity"] = serde_json::json!(63);
+    track
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_track_from_simplified() {
+    let full = full_track_json("11dFghVXANMlKmJXsNCbNl", &[]);
+    let server = MockServer::start(vec![MockResponse::new(200, &full.to_string())]);
+    let spotify = client_with(&server, Config::default());
+
+    let simplified =
+        serde_json::from_value(simplified_track_json(Some("11dFghVXANMlKmJXsNCbNl"))).unwrap();
+    let full = spotify.track_from_simplified(&simplified).await.unwrap();
+    assert_eq!(full.popularity, 63);
+    assert_eq!(server.requests()[0].path, "/tracks/11dFghVXANMlKmJXsNCbNl");
+
+    // Local tracks don't have an id
+    let local = serde_json::from_value(simplified_track_json(None)).unwrap();
+    let err = spotify.track_from_simplified(&local).await.unwrap_err();
+    assert!(matches!(err, ClientError::InvalidArgument(_)));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_track_available_markets() {
+    let full = full_track_json("11dFghVXANMlKmJXsNCbNl", &["ES", "US", "ZZ"]);
+    let server = MockServer::start(vec![MockResponse::new(200, &full.to_string())]);
+    let spotify = client_with(&server, Config::default());
+    let track = TrackId::from_id("11dFghVXANMlKmJXsNCbNl").unwrap();
+
+    let markets = spotify.track_available_markets(track).await.unwrap();
+    assert_eq!(
+        markets,
+        [
+            Country::Spain,
+            Country::UnitedStates,
+            Country::Unknown("ZZ".to_owned())
+        ]
+    );
+    assert_eq!(server.requests()[0].path, "/tracks/11dFghVXANMlKmJXsNCbNl");
+}
+
+fn followed_artists_page(ids: &[&str], after: Option<&str>) -> MockResponse {
+    let items = ids
+        .iter()
+        .map(|id| {
+            serde_json::json!({
+                "external_urls": {},
+                "followers": {"href": null, "total": 0},
+                "genres": [],
+                "href": "",
+                "id": id,
+                "images": [],
+                "name": id,
+                "popularity": 0,
+                "type": "artist",
+                "uri": format!("spotify:artist:{}", id)
+            })
+        })
+        .collect::<Vec<_>>();
+    let next = after.map(|after| {
+        format!(
+            "https://api.spotify.com/v1/me/following?type=artist&after={}",
+            after
+        )
+    });
+    let page = serde_json::json!({
+        "artists": {
+            "href": "",
+            "items": items,
+            "limit": 2,
+            "next": next,
+            "cursors": {"after": after},
+            "total": 3
+        }
+    });
+    MockResponse::new(200, &page.to_string())
+}
+
+/// The cursor-based paginator continues after the `after` cursor of the
+/// previous page until there's no next one.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_pagination_with_cursor() {
+    let server = MockServer::start(vec![
+        followed_artists_page(&["first", "second"], Some("second")),
+        followed_artists_page(&["third"], None),
+    ]);
+    let mut spotify = auth_code_client_with(&server);
+    spotify.config.pagination_chunks = 2;
+
+    let artists = spotify
+        .current_user_followed_artists()
+        .all_items()
+        .await
+        .unwrap();
+    let ids = artists.iter().map(|a| a.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["first", "second", "third"]);
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].path.contains("limit=2"));
+    assert!(!requests[0].path.contains("after="));
+    assert!(requests[1].path.contains("after=second"));
+}
+
+/// The timestamp is sent in the `yyyy-MM-ddTHH:mm:ss` format expected by
+/// Spotify, without a timezone.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_featured_playlists() {
+    let body = r#"{
+        "message": "Monday morning music, coming right up!",
+        "playlists": {
+            "href": "https://api.spotify.com/v1/browse/featured-playlists?offset=0&limit=1",
+            "items": [{
+                "collaborative": false,
+                "external_urls": {},
+                "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DX4WYpdgoIcn6",
+                "id": "37i9dQZF1DX4WYpdgoIcn6",
+                "images": [],
+                "name": "Chill Hits",
+                "owner": {
+                    "external_urls": {},
+                    "href": "https://api.spotify.com/v1/users/spotify",
+                    "id": "spotify",
+                    "type": "user",
+                    "uri": "spotify:user:spotify"
+                },
+                "public": null,
+                "snapshot_id": "MTU2NzM4MjQ3NiwwMDAwMDAwMA==",
+                "tracks": {
+                    "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DX4WYpdgoIcn6/tracks",
+                    "total": 100
+                },
+                "type": "playlist",
+                "uri": "spotify:playlist:37i9dQZF1DX4WYpdgoIcn6"
+            }],
+            "limit": 1,
+            "next": null,
+            "offset": 0,
+            "previous": null,
+            "total": 1
+        }
+    }"#;
+    let server = MockServer::start(vec![MockResponse::new(200, body)]);
+    let spotify = client_with(&server, Config::default());
+    let timestamp = Utc.timestamp_millis_opt(1414054800000).unwrap();
+
+    let featured = spotify
+        .featured_playlists(
+            Some("es_MX"),
+            Some(&Market::Country(Country::Mexico)),
+            Some(&timestamp),
+            Some(1),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(featured.message, "Monday morning music, coming right up!");
+    assert_eq!(featured.playlists.items.len(), 1);
+    assert_eq!(featured.playlists.items[0].name, "Chill Hits");
+    assert_eq!(featured.playlists.items[0].tracks.total, 100);
+
+    let requests = server.requests();
+    assert!(requests[0].path.starts_with("/browse/featured-playlists?"));
+    assert!(requests[0].path.contains("locale=es_MX"));
+    assert!(requests[0].path.contains("country=MX"));
+    assert!(requests[0]
+        .path
+        .contains("timestamp=2014-10-23T09%3A00%3A00"));
+    assert!(!requests[0].path.contains("%2B00"));
+    assert!(requests[0].path.contains("limit=1"));
+    assert!(!requests[0].path.contains("offset"));
+}
+
+/// The page of albums is unwrapped from the `albums` object in the response.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_new_releases() {
+    let page = r#"{"href": "", "items": [], "limit": 2, "next": null, "offset": 4, "previous": null, "total": 4}"#;
+    let body = format!(r#"{{"albums": {}}}"#, page);
+    let server = MockServer::start(vec![MockResponse::new(200, &body)]);
+    let spotify = client_with(&server, Config::default());
+
+    let page = spotify
+        .new_releases_manual(Some(&Market::Country(Country::Sweden)), Some(2), Some(4))
+        .await
+        .unwrap();
+    assert!(page.items.is_empty());
+    assert_eq!(page.offset, 4);
+    assert_eq!(page.total, 4);
+
+    let requests = server.requests();
+    assert!(requests[0].path.starts_with("/browse/new-releases?"));
+    assert!(requests[0].path.contains("country=SE"));
+    assert!(requests[0].path.contains("limit=2"));
+    assert!(requests[0].path.contains("offset=4"));
+}
+
+/// The artists are unwrapped from the `artists` object in the response.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_artist_related_artists() {
+    let body = r#"{
+        "artists": [{
+            "external_urls": {"spotify": "https://open.spotify.com/artist/5ZKMPRDHc7qElVJFh3uRqB"},
+            "followers": {"href": null, "total": 54573},
+            "genres": ["folk-pop", "indie folk"],
+            "href": "https://api.spotify.com/v1/artists/5ZKMPRDHc7qElVJFh3uRqB",
+            "id": "5ZKMPRDHc7qElVJFh3uRqB",
+            "images": [],
+            "name": "Wizo",
+            "popularity": 44,
+            "type": "artist",
+            "uri": "spotify:artist:5ZKMPRDHc7qElVJFh3uRqB"
+        }, {
+            "external_urls": {"spotify": "https://open.spotify.com/artist/0FI0kxP0BWurTz8cB8BBug"},
+            "followers": {"href": null, "total": 8812},
+            "genres": [],
+            "href": "https://api.spotify.com/v1/artists/0FI0kxP0BWurTz8cB8BBug",
+            "id": "0FI0kxP0BWurTz8cB8BBug",
+            "images": [{"height": 640, "url": "https://i.scdn.co/image/ab67616d0000b273", "width": 640}],
+            "name": "Pennywise",
+            "popularity": 58,
+            "type": "artist",
+            "uri": "spotify:artist:0FI0kxP0BWurTz8cB8BBug"
+        }]
+    }"#;
+    let server = MockServer::start(vec![MockResponse::new(200, body)]);
+    let spotify = client_with(&server, Config::default());
+    let artist = ArtistId::from_id("43ZHCT0cAZBISjO8DG9PnE").unwrap();
+
+    let artists = spotify.artist_related_artists(artist).await.unwrap();
+    let names = artists.iter().map(|a| a.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["Wizo", "Pennywise"]);
+    assert_eq!(artists[0].genres, ["folk-pop", "indie folk"]);
+    assert_eq!(artists[0].followers.total, 54573);
+    assert_eq!(artists[1].images[0].height, Some(640));
+    assert_eq!(
+        server.requests()[0].path,
+        "/artists/43ZHCT0cAZBISjO8DG9PnE/related-artists"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_user() {
+    let body = r#"{
+        "display_name": null,
+        "external_urls": {"spotify": "https://open.spotify.com/user/smedjan"},
+        "followers": {"href": null, "total": 12},
+        "href": "https://api.spotify.com/v1/users/smedjan",
+        "id": "smedjan",
+        "images": [],
+        "type": "user",
+        "uri": "spotify:user:smedjan"
+    }"#;
+    let server = MockServer::start(vec![MockResponse::new(200, body)]);
+    let spotify = client_with(&server, Config::default());
+    let user_id = UserId::from_id("smedjan").unwrap();
+
+    let user = spotify.user(user_id).await.unwrap();
+    assert_eq!(user.id, "smedjan");
+    assert_eq!(user.display_name, None);
+    assert_eq!(user.followers.unwrap().total, 12);
+    assert_eq!(server.requests()[0].path, "/users/smedjan");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_saved_audiobooks() {
+    let page = r#"{"href": "", "items": [], "limit": 20, "next": null, "offset": 0, "previous": null, "total": 0}"#;
+    let server = MockServer::start(vec![
+        MockResponse::new(200, page),
+        MockResponse::new(200, ""),
+        MockResponse::new(200, ""),
+        MockResponse::new(200, "[true, false]"),
+    ]);
+    let spotify = auth_code_client_with(&server);
+    let ids = [
+        AudiobookId::from_id("1HGw3J3NxZO1TP1BTtVhpZ").unwrap(),
+        AudiobookId::from_uri("spotify:audiobook:7iHfbu1YPACw6oZPAFJtqe").unwrap(),
+    ];
+
+    let saved = spotify
+        .current_user_saved_audiobooks_manual(Some(20), None)
+        .await
+        .unwrap();
+    assert!(saved.items.is_empty());
+    spotify
+        .current_user_saved_audiobooks_add(ids.iter().copied())
+        .await
+        .unwrap();
+    spotify
+        .current_user_saved_audiobooks_delete(ids.iter().copied())
+        .await
+        .unwrap();
+    let contains = spotify
+        .current_user_saved_audiobooks_contains(ids.iter().copied())
+        .await
+        .unwrap();
+    assert_eq!(contains, [true, false]);
+
+    let requests = server
+        .requests()
+        .into_iter()
+        .map(|r| (r.method, r.path))
+        .collect::<Vec<_>>();
+    let ids = "1HGw3J3NxZO1TP1BTtVhpZ,7iHfbu1YPACw6oZPAFJtqe";
+    assert_eq!(
+        requests,
+        [
+            ("GET".to_owned(), "/me/audiobooks?limit=20".to_owned()),
+            ("PUT".to_owned(), format!("/me/audiobooks?ids={}", ids)),
+            ("DELETE".to_owned(), format!("/me/audiobooks?ids={}", ids)),
+            (
+                "GET".to_owned(),
+                format!("/me/audiobooks/contains?ids={}", ids)
+            ),
+        ]
+    );
+}
+
+/// The market of `remove_users_saved_shows` is sent in the query, unlike the
+/// body of other `DELETE` requests.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_saved_shows() {
+    let page = r#"{"href": "", "items": [], "limit": 20, "next": null, "offset": 0, "previous": null, "total": 0}"#;
+    let server = MockServer::start(vec![
+        MockResponse::new(200, ""),
+        MockResponse::new(200, page),
+        MockResponse::new(200, "[true, false]"),
+        MockResponse::new(200, ""),
+        MockResponse::new(200, ""),
+    ]);
+    let spotify = auth_code_client_with(&server);
+    let ids = [
+        ShowId::from_id("38bS44xjbVVZ3No3ByF1dJ").unwrap(),
+        ShowId::from_uri("spotify:show:5CfCWKI5pZ28U0uOzXkDHe").unwrap(),
+    ];
+
+    spotify.save_shows(ids.iter().copied()).await.unwrap();
+    let saved = spotify.get_saved_show_manual(Some(20), None).await.unwrap();
+    assert!(saved.items.is_empty());
+    let contains = spotify
+        .check_users_saved_shows(ids.iter().copied())
+        .await
+        .unwrap();
+    assert_eq!(contains, [true, false]);
+    spotify
+        .remove_users_saved_shows(ids.iter().copied(), Some(&Market::Country(Country::Sweden)))
+        .await
+        .unwrap();
+    spotify
+        .remove_users_saved_shows(ids.iter().copied(), None)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    let ids = "38bS44xjbVVZ3No3ByF1dJ,5CfCWKI5pZ28U0uOzXkDHe";
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(requests[0].path, format!("/me/shows/?ids={}", ids));
+    assert_eq!(requests[1].method, "GET");
+    assert_eq!(requests[1].path, "/me/shows?limit=20");
+    assert_eq!(requests[2].method, "GET");
+    assert_eq!(
+        requests[2].path,
+        format!("/me/shows/contains?ids={}", ids.replace(',', "%2C"))
+    );
+    assert_eq!(requests[3].method, "DELETE");
+    assert_eq!(requests[3].path, format!("/me/shows?ids={}&market=SE", ids));
+    assert_eq!(requests[3].body, "{}");
+    assert_eq!(requests[4].path, format!("/me/shows?ids={}", ids));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_market_query() {
+    let tracks = serde_json::json!({
+        "tracks": [full_track_json("4iV5W9uYEdYUVa79Axb7Rh", &["US"])]
+    });
+    let server = MockServer::start(vec![
+        MockResponse::new(200, &tracks.to_string()),
+        MockResponse::new(200, r#"{"tracks": []}"#),
+    ]);
+    let spotify = client_with(&server, Config::default());
+    let artist = ArtistId::from_id("0OdUWJ0sBjDrqHygGUXeCF").unwrap();
+
+    // The tracks are unwrapped from the `tracks` object in the response
+    let tracks = spotify
+        .artist_top_tracks(artist, &Market::Country(Country::UnitedStates))
+        .await
+        .unwrap();
+    assert_eq!(tracks.len(), 1);
+    assert_eq!(tracks[0].id, Some("4iV5W9uYEdYUVa79Axb7Rh".to_owned()));
+    spotify
+        .artist_top_tracks(artist, &Market::FromToken)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].path,
+        "/artists/0OdUWJ0sBjDrqHygGUXeCF/top-tracks?market=US"
+    );
+    assert_eq!(
+        requests[1].path,
+        "/artists/0OdUWJ0sBjDrqHygGUXeCF/top-tracks?market=from_token"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_parse_error_snippet() {
+    let body = r#"{"unexpected": "shape"}"#;
+    let server = MockServer::start(vec![MockResponse::new(200, body)]);
+    let config = Config {
+        log_raw_responses: true,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    let id = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    match spotify.track(id).await.unwrap_err() {
+        ClientError::ParseResponse { body: snippet, .. } => assert_eq!(snippet, body),
+        err => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_check_follow() {
+    let server = MockServer::start(vec![MockResponse::new(
+        200,
+        "[true, false, false, true, false]",
+    )]);
+    let spotify = client_with(&server, Config::default());
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let ids = (0..6).map(|i| format!("user{}", i)).collect::<Vec<_>>();
+    let users = ids
+        .iter()
+        .map(|id| UserId::from_id(id).unwrap())
+        .collect::<Vec<_>>();
+
+    // Up to 5 users are checked at once, keeping their order
+    let follows = spotify
+        .playlist_check_follow(playlist, &users[..5])
+        .await
+        .unwrap();
+    assert_eq!(follows, [true, false, false, true, false]);
+    assert_eq!(
+        server.requests()[0].path,
+        "/playlists/37i9dQZF1DXcBWIGoYBM5M/followers/contains?ids=user0,user1,user2,user3,user4"
+    );
+
+    // More than 5 users fail before the request is sent
+    let err = spotify
+        .playlist_check_follow(playlist, &users)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InvalidArgument(_)));
+
+    // Nothing is requested without users
+    let follows = spotify.playlist_check_follow(playlist, &[]).await.unwrap();
+    assert!(follows.is_empty());
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_http_bytes() {
+    let server = MockServer::start(vec![
+        MockResponse::new(200, "posted"),
+        MockResponse::new(200, "put"),
+    ]);
+    let http = HttpClient::default();
+    let options = RequestOptions::default();
+    let mut headers = Headers::new();
+    headers.insert("x-custom".to_owned(), "value".to_owned());
+    let url = format!("{}upload", server.url());
+
+    let response = http
+        .post_bytes(&url, Some(&headers), "text/plain", b"raw body", &options)
+        .await
+        .unwrap();
+    assert_eq!(response, "posted");
+    let response = http
+        .put_bytes(&url, None, "application/octet-stream", &[0, 1, 2], &options)
+        .await
+        .unwrap();
+    assert_eq!(response, "put");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].header("content-type"), Some("text/plain"));
+    assert_eq!(requests[0].header("x-custom"), Some("value"));
+    assert_eq!(requests[0].body, "raw body");
+    assert_eq!(requests[1].method, "PUT");
+    assert_eq!(
+        requests[1].header("content-type"),
+        Some("application/octet-stream")
+    );
+    assert_eq!(requests[1].body.as_bytes(), [0, 1, 2]);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_snapshot_id() {
+    let server = MockServer::start(vec![
+        MockResponse::new(201, r#"{"snapshot_id": "first"}"#),
+        MockResponse::new(200, r#"{"snapshot_id": "second"}"#),
+        MockResponse::new(200, r#"{"snapshot_id": "third"}"#),
+    ]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let track = TrackId::from_id("0c6xIDDpzE81m2q797ordA").unwrap();
+
+    let result = spotify
+        .playlist_add_tracks(playlist, vec![track], None)
+        .await
+        .unwrap();
+    assert_eq!(result.snapshot_id, "first");
+
+    // The snapshot ID of each edit can be passed to the next one
+    let result = spotify
+        .playlist_reorder_tracks(
+            playlist,
+            None::<Vec<&TrackId>>,
+            Some(0),
+            Some(3),
+            None,
+            Some(&result.snapshot_id),
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.snapshot_id, "second");
+    let result = spotify
+        .playlist_remove_all_occurrences_of_tracks(playlist, vec![track], Some(&result.snapshot_id))
+        .await
+        .unwrap();
+    assert_eq!(result.snapshot_id, "third");
+
+    let requests = server.requests();
+    let bodies = requests
+        .iter()
+        .map(|req| serde_json::from_str::<serde_json::Value>(&req.body).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(requests[1].method, "PUT");
+    assert_eq!(bodies[1]["snapshot_id"], "first");
+    assert_eq!(requests[2].method, "DELETE");
+    assert_eq!(bodies[2]["snapshot_id"], "second");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_remove_specific_occurrences() {
+    let server = MockServer::start(vec![MockResponse::new(200, r#"{"snapshot_id": "new"}"#)]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let first = TrackId::from_id("4iV5W9uYEdYUVa79Axb7Rh").unwrap();
+    let second = TrackId::from_id("1301WleyT98MSxVHPZCA6M").unwrap();
+    let tracks = vec![
+        TrackPositions::new(first, vec![3, 0, 3]),
+        TrackPositions::new(second, vec![7]),
+    ];
+
+    let result = spotify
+        .playlist_remove_specific_occurrences_of_tracks(playlist, &tracks, Some("old"))
+        .await
+        .unwrap();
+    assert_eq!(result.snapshot_id, "new");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "DELETE");
+    assert_eq!(requests[0].path, "/playlists/37i9dQZF1DXcBWIGoYBM5M/tracks");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&requests[0].body).unwrap(),
+        serde_json::json!({
+            "tracks": [
+                {"uri": "spotify:track:4iV5W9uYEdYUVa79Axb7Rh", "positions": [0, 3]},
+                {"uri": "spotify:track:1301WleyT98MSxVHPZCA6M", "positions": [7]}
+            ],
+            "snapshot_id": "old"
+        })
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_current_user_top_items() {
+    let page = r#"{
+        "href": "",
+        "items": [],
+        "limit": 10,
+        "next": null,
+        "offset": 5,
+        "previous": null,
+        "total": 0
+    }"#;
+    let server = MockServer::start(vec![MockResponse::new(200, page); 2]);
+    let spotify = auth_code_client_with(&server);
+
+    spotify
+        .current_user_top_artists_manual(Some(&TimeRange::ShortTerm), Some(10), Some(5))
+        .await
+        .unwrap();
+    spotify
+        .current_user_top_tracks_manual(Some(&TimeRange::LongTerm), None, None)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    let (path, query) = requests[0].path.split_once('?').unwrap();
+    assert_eq!(path, "/me/top/artists");
+    let mut query = query.split('&').collect::<Vec<_>>();
+    query.sort_unstable();
+    assert_eq!(query, ["limit=10", "offset=5", "time_range=short_term"]);
+    assert_eq!(requests[1].path, "/me/top/tracks?time_range=long_term");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_fields() {
+    let server = MockServer::start(vec![
+        MockResponse::new(
+            200,
+            r#"{"tracks": {"items": [{"track": {"name": "Song", "id": "id"}}]}}"#,
+        );
+        2
+    ]);
+    let spotify = auth_code_client_with(&server);
+
+    let fields = "tracks.items(track(name,id))";
+    let response = spotify
+        .get_raw("playlists/37i9dQZF1DXcBWIGoYBM5M", &[("fields", fields)])
+        .await
+        .unwrap();
+    assert_eq!(response["tracks"]["items"][0]["track"]["name"], "Song");
+
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    // The partial response can't be parsed into the full model
+    let err = spotify
+        .playlist(playlist, Some(fields), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::ParseResponse { .. }));
+
+    let requests = server.requests();
+    let expected =
+        "/playlists/37i9dQZF1DXcBWIGoYBM5M?fields=tracks.items%28track%28name%2Cid%29%29";
+    assert_eq!(requests[0].path, expected);
+    assert_eq!(requests[1].path, expected);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_follow() {
+    let server = MockServer::start(vec![MockResponse::new(200, ""); 3]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+
+    spotify
+        .playlist_follow(playlist, Some(false))
+        .await
+        .unwrap();
+    spotify.playlist_follow(playlist, Some(true)).await.unwrap();
+    spotify.playlist_follow(playlist, None).await.unwrap();
+
+    let requests = server.requests();
+    let bodies = requests
+        .iter()
+        .map(|req| serde_json::from_str::<serde_json::Value>(&req.body).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(
+        requests[0].path,
+        "/playlists/37i9dQZF1DXcBWIGoYBM5M/followers"
+    );
+    assert_eq!(bodies[0], serde_json::json!({"public": false}));
+    assert_eq!(bodies[1], serde_json::json!({"public": true}));
+    assert_eq!(bodies[2], serde_json::json!({}));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_several_shows_and_episodes() {
+    let nulls = |n| vec!["null"; n].join(",");
+    let server = MockServer::start(vec![
+        MockResponse::new(200, &format!(r#"{{"shows": [{}]}}"#, nulls(50))),
+        MockResponse::new(200, r#"{"shows": [null]}"#),
+        MockResponse::new(200, r#"{"episodes": [null, null]}"#),
+    ]);
+    let spotify = client_with(&server, Config::default());
+
+    let ids = (0..51).map(|i| format!("{:0>22}", i)).collect::<Vec<_>>();
+    let show_ids = ids
+        .iter()
+        .map(|id| ShowId::from_id(id).unwrap())
+        .collect::<Vec<_>>();
+    let shows = spotify.get_several_shows(show_ids, None).await.unwrap();
+    assert_eq!(shows.len(), 51);
+    assert!(shows.iter().all(Option::is_none));
+
+    let episode_ids = ids[..2]
+        .iter()
+        .map(|id| EpisodeId::from_id(id).unwrap())
+        .collect::<Vec<_>>();
+    let episodes = spotify
+        .get_several_episodes(episode_ids, None)
+        .await
+        .unwrap();
+    assert_eq!(episodes, [None, None]);
+
+    // More than 50 shows are requested in chunks
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    let count_ids = |path: &str| path.split("%2C").count();
+    assert_eq!(count_ids(&requests[0].path), 50);
+    assert_eq!(requests[1].path, format!("/shows?ids={}", ids[50]));
+    assert!(requests[2].path.starts_with("/episodes?ids="));
+    assert_eq!(count_ids(&requests[2].path), 2);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_user_playlist_create() {
+    let playlist = r#"{
+        "collaborative": true,
+        "description": null,
+        "external_urls": {},
+        "followers": {"href": null, "total": 0},
+        "href": "https://api.spotify.com/v1/playlists/3cEYpjA9oz9GiPac4AsH4n",
+        "id": "3cEYpjA9oz9GiPac4AsH4n",
+        "images": [],
+        "name": "Road trip",
+        "owner": {
+            "display_name": "JMPerez²",
+            "external_urls": {},
+            "href": "https://api.spotify.com/v1/users/jmperezperez",
+            "id": "jmperezperez",
+            "type": "user",
+            "uri": "spotify:user:jmperezperez"
+        },
+        "public": false,
+        "snapshot_id": "MSw2ZDg3MjgwNTVlMDQ4MjNkNDg0OTk2ZjM0MjFkNDY3ZGE3MTMzNjY4",
+        "tracks": {
+            "href": "https://api.spotify.com/v1/playlists/3cEYpjA9oz9GiPac4AsH4n/tracks",
+            "items": [],
+            "limit": 100,
+            "next": null,
+            "offset": 0,
+            "previous": null,
+            "total": 0
+        },
+        "type": "playlist",
+        "uri": "spotify:playlist:3cEYpjA9oz9GiPac4AsH4n"
+    }"#;
+    let server = MockServer::start(vec![MockResponse::new(201, playlist)]);
+    let spotify = auth_code_client_with(&server);
+    let user = UserId::from_id("jmperezperez").unwrap();
+
+    let created = spotify
+        .user_playlist_create(user, "Road trip", Some(false), Some(true), Some("Songs"))
+        .await
+        .unwrap();
+    assert_eq!(created.id, "3cEYpjA9oz9GiPac4AsH4n");
+    assert!(created.collaborative);
+    assert_eq!(created.description, None);
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/users/jmperezperez/playlists");
+    let body = serde_json::from_str::<serde_json::Value>(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "name": "Road trip",
+            "public": false,
+            "collaborative": true,
+            "description": "Songs"
+        })
+    );
+
+    // Collaborative playlists can't be public
+    let err = spotify
+        .user_playlist_create(user, "Road trip", None, Some(true), None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InvalidArgument(_)));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_change_detail() {
+    let server = MockServer::start(vec![MockResponse::new(200, ""); 2]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("3cEYpjA9oz9GiPac4AsH4n").unwrap();
+
+    spotify
+        .playlist_change_detail(playlist, Some("Road trip"), None, None, Some("Songs"))
+        .await
+        .unwrap();
+    spotify
+        .playlist_change_detail(playlist, None, Some(false), Some(true), None)
+        .await
+        .unwrap();
+
+    // Only the given details are sent
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(requests[0].path, "/playlists/3cEYpjA9oz9GiPac4AsH4n");
+    let bodies = requests
+        .iter()
+        .map(|req| serde_json::from_str::<serde_json::Value>(&req.body).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        bodies[0],
+        serde_json::json!({"name": "Road trip", "description": "Songs"})
+    );
+    assert_eq!(
+        bodies[1],
+        serde_json::json!({"public": false, "collaborative": true})
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_upload_cover_image() {
+    let server = MockServer::start(vec![MockResponse::new(202, "")]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let image = "/9j/4AAQSkZJRgABAQEASABIAAD/2wBDAP//////////////////////////////";
+
+    spotify
+        .playlist_upload_cover_image(playlist, image)
+        .await
+        .unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "PUT");
+    assert_eq!(request.path, "/playlists/37i9dQZF1DXcBWIGoYBM5M/images");
+    assert_eq!(request.header("content-type"), Some("image/jpeg"));
+    assert_eq!(
+        request.header("authorization"),
+        Some("Bearer test-access-token")
+    );
+    assert_eq!(request.body, image);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playlist_upload_empty_cover_image() {
+    let server = MockServer::start(vec![]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+
+    for image in ["", " \n"] {
+        let err = spotify
+            .playlist_upload_cover_image(playlist, image)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ClientError::InvalidArgument(_)));
+    }
+    assert!(server.requests().is_empty());
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_recently_played_time_limits() {
+    let page = r#"{"href": "", "items": [], "limit": 10, "next": null, "cursors": {}}"#;
+    let server = MockServer::start(vec![MockResponse::new(200, page); 3]);
+    let spotify = auth_code_client_with(&server);
+    let time = Utc.timestamp_millis_opt(1481661844589).unwrap();
+
+    spotify
+        .current_user_recently_played(Some(10), Some(TimeLimits::Before(time)))
+        .await
+        .unwrap();
+    spotify
+        .current_user_recently_played(None, Some(TimeLimits::After(time)))
+        .await
+        .unwrap();
+    let page = spotify
+        .current_user_recently_played(None, None)
+        .await
+        .unwrap();
+    assert_eq!(page.cursors.after, None);
+
+    let requests = server.requests();
+    assert!(requests[0].path.starts_with("/me/player/recently-played?"));
+    assert!(requests[0].path.contains("before=1481661844589"));
+    assert!(requests[0].path.contains("limit=10"));
+    assert_eq!(
+        requests[1].path,
+        "/me/player/recently-played?after=1481661844589"
+    );
+    assert_eq!(requests[2].path, "/me/player/recently-played");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_playback_control_no_content() {
+    // Spotify replies to the playback controls with an empty body, which
+    // shouldn't be parsed
+    let server = MockServer::start(vec![MockResponse::new(204, ""); 11]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let track = TrackId::from_id("0c6xIDDpzE81m2q797ordA").unwrap();
+
+    spotify
+        .start_context_playback(playlist, None, None::<Offset<Track>>, None)
+        .await
+        .unwrap();
+    spotify
+        .start_uris_playback(vec![track], None, None, None)
+        .await
+        .unwrap();
+    spotify.pause_playback(None).await.unwrap();
+    spotify.next_track(None).await.unwrap();
+    spotify.previous_track(None).await.unwrap();
+    spotify.seek_position(1000, None).await.unwrap();
+    spotify.repeat(&RepeatState::Track, None).await.unwrap();
+    spotify.volume(50, None).await.unwrap();
+    spotify.shuffle(true, None).await.unwrap();
+    spotify.add_item_to_queue(track, None).await.unwrap();
+    spotify.transfer_playback("device", None).await.unwrap();
+
+    assert_eq!(server.requests().len(), 11);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_repeat_query() {
+    let server = MockServer::start(vec![MockResponse::new(204, ""); 3]);
+    let spotify = auth_code_client_with(&server);
+
+    spotify.repeat(&RepeatState::Off, None).await.unwrap();
+    spotify.repeat(&RepeatState::Track, None).await.unwrap();
+    spotify
+        .repeat(&RepeatState::Context, Some("device"))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/me/player/repeat?state=off");
+    assert_eq!(requests[1].path, "/me/player/repeat?state=track");
+    assert_eq!(
+        requests[2].path,
+        "/me/player/repeat?state=context&device_id=device"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_start_mixed_uris_playback() {
+    let server = MockServer::start(vec![MockResponse::new(204, "")]);
+    let spotify = auth_code_client_with(&server);
+    let track = TrackId::from_id("0c6xIDDpzE81m2q797ordA").unwrap();
+    let episode = EpisodeId::from_id("512ojhOuo1ktJprKbVcKyQ").unwrap();
+
+    let uris: Vec<PlayableId> = vec![track.into(), episode.into()];
+    spotify
+        .start_uris_playback(uris, None, Some(PlayableOffset::for_uri(episode)), None)
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    let body = serde_json::from_str::<serde_json::Value>(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "uris": [
+                "spotify:track:0c6xIDDpzE81m2q797ordA",
+                "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+            ],
+            "offset": {"uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"}
+        })
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_start_playback_offset() {
+    let server = MockServer::start(vec![MockResponse::new(204, ""); 3]);
+    let spotify = auth_code_client_with(&server);
+    let playlist = PlaylistId::from_id("37i9dQZF1DXcBWIGoYBM5M").unwrap();
+    let track = TrackId::from_id("0c6xIDDpzE81m2q797ordA").unwrap();
+
+    spotify
+        .start_context_playback(
+            playlist,
+            Some("device"),
+            Some(Offset::<Track>::for_position(5)),
+            Some(std::time::Duration::from_secs(30)),
+        )
+        .await
+        .unwrap();
+    spotify
+        .start_context_playback(playlist, None, Some(Offset::for_uri(track)), None)
+        .await
+        .unwrap();
+    spotify
+        .start_uris_playback(
+            vec![track],
+            None,
+            Some(PlayableOffset::for_uri(track)),
+            Some(1500),
+        )
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    let bodies = requests
+        .iter()
+        .map(|req| serde_json::from_str::<serde_json::Value>(&req.body).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(requests[0].path, "/me/player/play?device_id=device");
+    assert_eq!(
+        requests[0].header("authorization"),
+        Some("Bearer test-access-token")
+    );
+    assert_eq!(
+        bodies[0],
+        serde_json::json!({
+            "context_uri": "spotify:playlist:37i9dQZF1DXcBWIGoYBM5M",
+            "offset": {"position": 5},
+            "position_ms": 30000
+        })
+    );
+    assert_eq!(
+        bodies[1],
+        serde_json::json!({
+            "context_uri": "spotify:playlist:37i9dQZF1DXcBWIGoYBM5M",
+            "offset": {"uri": "spotify:track:0c6xIDDpzE81m2q797ordA"}
+        })
+    );
+    assert_eq!(
+        bodies[2],
+        serde_json::json!({
+            "uris": ["spotify:track:0c6xIDDpzE81m2q797ordA"],
+            "offset": {"uri": "spotify:track:0c6xIDDpzE81m2q797ordA"},
+            "position_ms": 1500
+        })
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_volume_range() {
+    let server = MockServer::start(vec![MockResponse::new(204, ""); 2]);
+    let spotify = auth_code_client_with(&server);
+
+    spotify.volume(0, None).await.unwrap();
+    spotify.volume(100, None).await.unwrap();
+    let err = spotify.volume(101, None).await.unwrap_err();
+    assert!(matches!(err, ClientError::InvalidArgument(_)));
+
+    // The invalid volume isn't sent
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].path, "/me/player/volume?volume_percent=0");
+    assert_eq!(requests[1].path, "/me/player/volume?volume_percent=100");
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_seek_position() {
+    let server = MockServer::start(vec![MockResponse::new(204, ""); 2]);
+    let spotify = auth_code_client_with(&server);
+
+    spotify.seek_position(25000, None).await.unwrap();
+    spotify.seek_position(25000, Some("device")).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "PUT");
+    assert_eq!(requests[0].path, "/me/player/seek?position_ms=25000");
+    assert_eq!(
+        requests[1].path,
+        "/me/player/seek?position_ms=25000&device_id=device"
+    );
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_recommendations() {
+    let server = MockServer::start(vec![MockResponse::new(
+        200,
+        r#"{"seeds": [], "tracks": []}"#,
+    )]);
+    let spotify = client_with(&server, Config::default());
+
+    let artist = ArtistId::from_id("4NHQUGzhtTLFvgF5SZesLK").unwrap();
+    let track = TrackId::from_id("0c6xIDDpzE81m2q797ordA").unwrap();
+    let attributes = RecommendationsAttributes::new()
+        .seed_artists(vec![artist])
+        .seed_genres(vec!["rock"])
+        .seed_tracks(vec![track])
+        .min(RecommendationsAttribute::Tempo(100.0))
+        .target(RecommendationsAttribute::Energy(0.5));
+    spotify
+        .recommendations(&attributes, None, Some(10))
+        .await
+        .unwrap();
+
+    let requests = server.requests();
+    let (path, query) = requests[0].path.split_once('?').unwrap();
+    let mut query = query.split('&').collect::<Vec<_>>();
+    query.sort_unstable();
+    assert_eq!(path, "/recommendations");
+    assert_eq!(
+        query,
+        [
+            "limit=10",
+            "min_tempo=100",
+            "seed_artists=4NHQUGzhtTLFvgF5SZesLK",
+            "seed_genres=rock",
+            "seed_tracks=0c6xIDDpzE81m2q797ordA",
+            "target_energy=0.5",
+        ]
+    );
+
+    // Invalid requests aren't sent
+    let attributes = attributes.seed_genres(vec!["jazz", "pop", "techno"]);
+    let err = spotify
+        .recommendations(&attributes, None, None)
+        .await
+        .unwrap_err();
+    match err {
+        ClientError::InvalidArgument(msg) => {
+            assert_eq!(msg, RecommendationsError::TooManySeeds(6).to_string())
+        }
+        err => panic!("unexpected error: {:?}", err),
+    }
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_available_genre_seeds() {
+    let server = MockServer::start(vec![MockResponse::new(
+        200,
+        r#"{"genres": ["acoustic", "afrobeat", "alt-rock"]}"#,
+    )]);
+    let spotify = client_with(&server, Config::default());
+
+    let genres = spotify.available_genre_seeds().await.unwrap();
+    assert_eq!(genres, ["acoustic", "afrobeat", "alt-rock"]);
+    assert_eq!(
+        server.requests()[0].path,
+        "/recommendations/available-genre-seeds"
+    );
+}
+
+#[cfg(feature = "__async")]
+#[tokio::test]
+async fn test_artists_albums() {
+    let ids = (0..8).map(|i| format!("{:0>22}", i)).collect::<Vec<_>>();
+    let server = MockServer::start_with(ids.len(), |req| {
+        // The earlier artists take longer to reply, so that the responses
+        // arrive in a different order than the requests
+        let index = req.path[9..31].parse::<u64>().unwrap();
+        let body = format!(
+            r#"{{"href": "{}", "items": [], "limit": 20, "next": null,
+                "offset": 0, "previous": null, "total": 0}}"#,
+            req.path
+        );
+        MockResponse::new(200, &body).delay(std::time::Duration::from_millis(200 - index * 20))
+    });
+    let config = Config {
+        max_concurrent_requests: 3,
+        ..Default::default()
+    };
+    let spotify = client_with(&server, config);
+
+    let artist_ids = ids
+        .iter()
+        .map(|id| rspotify::model::ArtistId::from_id(id).unwrap())
+        .collect::<Vec<_>>();
+    let pages = spotify.artists_albums(&artist_ids).await.unwrap();
+
+    let hrefs = pages.into_iter().map(|page| page.href).collect::<Vec<_>>();
+    let expected = ids
+        .iter()
+        .map(|id| format!("/artists/{}/albums", id))
+        .collect::<Vec<_>>();
+    assert_eq!(hrefs, expected);
+    assert_eq!(server.requests().len(), ids.len());
+    assert_eq!(server.max_concurrent(), 3);
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_saved_episodes_manual() {
+    let body = format!(
+        r#"
+{{
+  "href": "https://api.spotify.com/v1/me/episodes?offset=10&limit=1",
+  "items": [{{"added_at": "2021-03-02T10:00:00Z", "episode": {}}}],
+  "limit": 1,
+  "next": "https://api.spotify.com/v1/me/episodes?offset=11&limit=1",
+  "offset": 10,
+  "previous": "https://api.spotify.com/v1/me/episodes?offset=9&limit=1",
+  "total": 20
+}}
+"#,
+        EPISODE
+    );
+    let server = MockServer::start(vec![MockResponse::new(200, &body)]);
+    let spotify = auth_code_client_with(&server);
+
+    let page = spotify
+        .current_user_saved_episodes_manual(Some(1), Some(10))
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].episode.name, "Async Rust");
+
+    // A single page is requested, even though there are more
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].path.starts_with("/me/episodes?"));
+    assert!(requests[0].path.contains("limit=1"));
+    assert!(requests[0].path.contains("offset=10"));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_custom_auth_urls() {
+    let server = MockServer::start(vec![MockResponse::new(200, TOKEN_RESPONSE)]);
+    let creds = Credentials::new("client-id", "client-secret");
+    let mut spotify = ClientCredsSpotify::with_config(creds, accounts_config(&server));
+
+    spotify.request_token().await.unwrap();
+    let token = spotify.token.as_ref().unwrap();
+    assert_eq!(token.access_token, "new-access-token");
+    assert_eq!(token.expires_in, Duration::seconds(3600));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/api/token");
+    assert_eq!(
+        requests[0].header("authorization"),
+        Some("Basic Y2xpZW50LWlkOmNsaWVudC1zZWNyZXQ=")
+    );
+    assert_eq!(requests[0].body, "grant_type=client_credentials");
+
+    let spotify = AuthCodeSpotify::with_config(
+        Credentials::default(),
+        OAuth::default(),
+        accounts_config(&server),
+    );
+    let url = spotify.get_authorize_url(false).unwrap();
+    assert!(url.starts_with(&format!("{}authorize?", server.url())));
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_refresh_token_if_needed() {
+    let server = MockServer::start(vec![MockResponse::new(200, TOKEN_RESPONSE)]);
+    let mut spotify = AuthCodeSpotify::with_config(
+        Credentials::default(),
+        OAuth::default(),
+        accounts_config(&server),
+    );
+
+    // A valid token isn't refreshed
+    spotify.token = Some(Token {
+        access_token: "valid-access-token".to_owned(),
+        expires_at: Some(Utc::now() + Duration::hours(1)),
+        refresh_token: Some("refresh-token".to_owned()),
+        ..Default::default()
+    });
+    let refreshed = spotify.refresh_token_if_needed().await.unwrap();
+    assert!(!refreshed);
+    assert!(server.requests().is_empty());
+
+    // An expired one is
+    spotify.token = Some(Token {
+        access_token: "expired-access-token".to_owned(),
+        expires_at: Some(Utc::now() - Duration::hours(1)),
+        refresh_token: Some("refresh-token".to_owned()),
+        ..Default::default()
+    });
+    let refreshed = spotify.refresh_token_if_needed().await.unwrap();
+    assert!(refreshed);
+    let token = spotify.token.as_ref().unwrap();
+    assert_eq!(token.access_token, "new-access-token");
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].body.contains("refresh_token=refresh-token"));
+
+    // It can't be refreshed without a refresh token
+    spotify.token = Some(Token {
+        expires_at: Some(Utc::now() - Duration::hours(1)),
+        ..Default::default()
+    });
+    let err = spotify.refresh_token_if_needed().await.unwrap_err();
+    assert!(matches!(err, ClientError::InvalidAuth(_)));
+}
+
+/// The callback is invoked with the refreshed token, for both of the clients
+/// that may refresh it.
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_on_token_refresh() {
+    use std::sync::{Arc, Mutex};
+
+    let server = MockServer::start(vec![MockResponse::new(200, TOKEN_RESPONSE); 2]);
+    let refreshed = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&refreshed);
+    let config = Config {
+        on_token_refresh: Some(TokenRefreshHook::new(move |token| {
+            received
+                .lock()
+                .unwrap()
+                .push((token.access_token.clone(), token.refresh_token.clone()));
+        })),
+        ..accounts_config(&server)
+    };
+
+    let mut spotify =
+        AuthCodeSpotify::with_config(Credentials::default(), OAuth::default(), config.clone());
+    spotify.refresh_token("refresh-token").await.unwrap();
+    let mut spotify =
+        AuthCodePkceSpotify::with_config(Credentials::default(), OAuth::default(), config);
+    spotify.refresh_token("refresh-token").await.unwrap();
+
+    let expected = (
+        "new-access-token".to_owned(),
+        Some("refresh-token".to_owned()),
+    );
+    assert_eq!(*refreshed.lock().unwrap(), [expected.clone(), expected]);
+}
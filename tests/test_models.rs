@@ -35,6 +35,7 @@ fn test_simplified_track() {
 
 "#;
     let track: SimplifiedTrack = serde_json::from_str(&json_str).unwrap();
+    assert_eq!(track.id().unwrap().id(), "2TpxZ7JUBn3uw46aR7qd6V");
     let duration = Duration::from_millis(276773);
     assert_eq!(track.duration, duration);
 }
@@ -66,6 +67,33 @@ fn test_public_user() {
         "#;
     let user: PublicUser = serde_json::from_str(&json_str).unwrap();
     assert_eq!(user.id, "wizzler".to_string());
+    assert_eq!(user.id().unwrap().id(), "wizzler");
+}
+
+#[test]
+fn test_public_user_without_display_name() {
+    let json_str = r#"
+        {
+            "display_name": null,
+            "external_urls": {
+                "spotify": "https://open.spotify.com/user/smedjan"
+            },
+            "followers": {
+                "href": null,
+                "total": 0
+            },
+            "href": "https://api.spotify.com/v1/users/smedjan",
+            "id": "smedjan",
+            "images": [],
+            "type": "user",
+            "uri": "spotify:user:smedjan"
+        }
+        "#;
+    let user: PublicUser = serde_json::from_str(json_str).unwrap();
+    assert_eq!(user.display_name, None);
+    assert_eq!(user.id().unwrap().id(), "smedjan");
+    assert_eq!(user.followers.unwrap().total, 0);
+    assert!(user.images.is_empty());
 }
 
 #[test]
@@ -96,6 +124,46 @@ fn test_private_user() {
         "#;
     let private_user: PrivateUser = serde_json::from_str(&json_str).unwrap();
     assert_eq!(private_user.country.unwrap(), Country::UnitedStates);
+    assert_eq!(private_user.product, Some(SubscriptionLevel::Open));
+}
+
+#[test]
+fn test_private_user_free_without_email() {
+    let json_str = r#"
+        {
+            "country": "SE",
+            "display_name": null,
+            "explicit_content": {
+              "filter_enabled": true,
+              "filter_locked": false
+            },
+            "external_urls": {
+              "spotify": "https://open.spotify.com/user/smedjan"
+            },
+            "followers": {
+              "href": null,
+              "total": 3
+            },
+            "href": "https://api.spotify.com/v1/users/smedjan",
+            "id": "smedjan",
+            "images": [],
+            "product": "free",
+            "type": "user",
+            "uri": "spotify:user:smedjan"
+        }
+        "#;
+    let private_user: PrivateUser = serde_json::from_str(json_str).unwrap();
+    assert_eq!(private_user.email, None);
+    assert_eq!(private_user.product, Some(SubscriptionLevel::Free));
+    assert!(private_user.explicit_content.unwrap().filter_enabled);
+    assert_eq!(private_user.images, Some(Vec::new()));
+
+    // Unknown subscription levels are kept as they are
+    let level: SubscriptionLevel = serde_json::from_str(r#""family""#).unwrap();
+    assert_eq!(level, SubscriptionLevel::Unknown("family".to_owned()));
+    assert_eq!(serde_json::to_string(&level).unwrap(), r#""family""#);
+    let level: SubscriptionLevel = serde_json::from_str(r#""premium""#).unwrap();
+    assert_eq!(level, SubscriptionLevel::Premium);
 }
 
 #[test]
@@ -130,6 +198,7 @@ fn test_full_artist() {
     let full_artist: FullArtist = serde_json::from_str(&json_str).unwrap();
     assert_eq!(full_artist.name, "Band of Horses");
     assert_eq!(full_artist.followers.total, 833247);
+    assert_eq!(full_artist.id().unwrap().id(), "0OdUWJ0sBjDrqHygGUXeCF");
 }
 
 #[test]
@@ -241,6 +310,7 @@ fn test_full_episode() {
     }
         "#;
     let full_episode: FullEpisode = serde_json::from_str(&json_str).unwrap();
+    assert_eq!(full_episode.id().unwrap().id(), "512ojhOuo1ktJprKbVcKyQ");
     assert_eq!(full_episode.release_date_precision, DatePrecision::Day);
     let duration = Duration::from_millis(1502795);
     assert_eq!(full_episode.duration, duration);
@@ -304,6 +374,94 @@ fn test_audio_analysis_segments() {
     assert_eq!(segment.time_interval.start, 252.15601);
 }
 
+#[test]
+fn test_audio_analysis() {
+    // Trimmed from a real response; the track fingerprints are omitted by
+    // Spotify for some tracks.
+    let json_str = r#"
+{
+  "meta": {
+    "analyzer_version": "4.0.0",
+    "platform": "Linux",
+    "detailed_status": "OK",
+    "status_code": 0,
+    "timestamp": 1495193577,
+    "analysis_time": 6.93906,
+    "input_process": "libvorbisfile L+R 44100->22050"
+  },
+  "track": {
+    "num_samples": 4585515,
+    "duration": 207.95985,
+    "sample_md5": "",
+    "offset_seconds": 0,
+    "window_seconds": 0,
+    "analysis_sample_rate": 22050,
+    "analysis_channels": 1,
+    "end_of_fade_in": 0,
+    "start_of_fade_out": 201.13705,
+    "loudness": -5.883,
+    "tempo": 118.211,
+    "tempo_confidence": 0.73,
+    "time_signature": 4,
+    "time_signature_confidence": 0.994,
+    "key": -1,
+    "key_confidence": 0,
+    "mode": 0,
+    "mode_confidence": 0.483
+  },
+  "bars": [
+    {"start": 0.49567, "duration": 2.18749, "confidence": 0.925}
+  ],
+  "beats": [
+    {"start": 0.49567, "duration": 0.5468, "confidence": 0.925},
+    {"start": 1.04247, "duration": 0.54683, "confidence": 0.867}
+  ],
+  "sections": [
+    {
+      "start": 0,
+      "duration": 6.97092,
+      "confidence": 1,
+      "loudness": -14.938,
+      "tempo": 113.178,
+      "tempo_confidence": 0.647,
+      "key": 9,
+      "key_confidence": 0.297,
+      "mode": -1,
+      "mode_confidence": 0.471,
+      "time_signature": 4,
+      "time_signature_confidence": 1
+    }
+  ],
+  "segments": [
+    {
+      "start": 0.70154,
+      "duration": 0.19891,
+      "confidence": 0.435,
+      "loudness_start": -23.053,
+      "loudness_max_time": 0.07305,
+      "loudness_max": -14.25,
+      "pitches": [0.212, 0.141, 0.294, 0.078, 0.119, 0.163, 0.108, 0.147, 0.093, 1, 0.154, 0.111],
+      "timbre": [42.115, 64.373, -0.233, 6.924, -13.034, 40.591, -7.564, 0.357, 30.045, -9.451, 7.023, -12.234],
+      "tempo": 118.211
+    }
+  ],
+  "tatums": [
+    {"start": 0.49567, "duration": 0.2734, "confidence": 0.925}
+  ]
+}
+"#;
+    let analysis: AudioAnalysis = serde_json::from_str(json_str).unwrap();
+    assert_eq!(analysis.meta.status_code, 0);
+    assert_eq!(analysis.track.key, -1);
+    assert_eq!(analysis.track.codestring, "");
+    assert_eq!(analysis.bars.len(), 1);
+    assert_eq!(analysis.beats[1].start, 1.04247);
+    assert_eq!(analysis.sections[0].mode, Modality::NoResult);
+    assert_eq!(analysis.segments[0].loudness_end, None);
+    assert_eq!(analysis.segments[0].pitches.len(), 12);
+    assert_eq!(analysis.tatums[0].duration, 0.2734);
+}
+
 #[test]
 fn test_actions() {
     let json_str = r#"
@@ -317,6 +475,63 @@ fn test_actions() {
     assert_eq!(actions.disallows[0], DisallowKey::Resuming);
 }
 
+#[test]
+fn test_recommendations_attributes() {
+    let artist = ArtistId::from_id("4NHQUGzhtTLFvgF5SZesLK").unwrap();
+    let track = TrackId::from_id("0c6xIDDpzE81m2q797ordA").unwrap();
+    let attributes = RecommendationsAttributes::new()
+        .seed_artists(vec![artist])
+        .seed_genres(vec!["rock", "jazz"])
+        .seed_tracks(vec![track])
+        .min(RecommendationsAttribute::Energy(0.4))
+        .max(RecommendationsAttribute::Duration(Duration::from_secs(300)))
+        .target(RecommendationsAttribute::Energy(0.8))
+        .target(RecommendationsAttribute::Mode(Modality::Major))
+        // Overwrites the previous target energy
+        .target(RecommendationsAttribute::Energy(0.6));
+
+    let query = attributes.to_query().unwrap();
+    let query = query
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        query,
+        vec![
+            ("seed_artists", "4NHQUGzhtTLFvgF5SZesLK"),
+            ("seed_genres", "rock,jazz"),
+            ("seed_tracks", "0c6xIDDpzE81m2q797ordA"),
+            ("min_energy", "0.4"),
+            ("max_duration_ms", "300000"),
+            ("target_energy", "0.6"),
+            ("target_mode", "1"),
+        ]
+    );
+}
+
+#[test]
+fn test_recommendations_attributes_invalid() {
+    let attributes = RecommendationsAttributes::new();
+    assert_eq!(attributes.to_query(), Err(RecommendationsError::NoSeeds));
+
+    let attributes =
+        RecommendationsAttributes::new().seed_genres(vec!["a", "b", "c", "d", "e", "f"]);
+    assert_eq!(
+        attributes.to_query(),
+        Err(RecommendationsError::TooManySeeds(6))
+    );
+
+    let attributes = RecommendationsAttributes::new()
+        .seed_genres(vec!["rock"])
+        .min(RecommendationsAttribute::Tempo(100.0))
+        .max(RecommendationsAttribute::Tempo(140.0))
+        .target(RecommendationsAttribute::Tempo(150.0));
+    assert_eq!(
+        attributes.to_query(),
+        Err(RecommendationsError::InvalidRange("tempo"))
+    );
+}
+
 #[test]
 fn test_recommendations_seed() {
     let json_str = r#"
@@ -463,6 +678,7 @@ fn test_full_playlist() {
         }
         "#.replace("json_str_images", json_str_images).replace("json_str_simplified_artists", json_str_simplified_artists);
     let full_playlist: FullPlaylist = serde_json::from_str(&json_str).unwrap();
+    assert_eq!(full_playlist.id().unwrap().id(), "3cEYpjA9oz9GiPac4AsH4n");
     assert_eq!(
         full_playlist.uri,
         "spotify:playlist:3cEYpjA9oz9GiPac4AsH4n".to_string()
@@ -574,6 +790,11 @@ fn test_full_track() {
 }
     "#;
     let full_track: FullTrack = serde_json::from_str(&json).unwrap();
+    assert_eq!(full_track.id().unwrap().id(), "11dFghVXANMlKmJXsNCbNl");
+    assert_eq!(
+        full_track.album.id().unwrap().id(),
+        "0tGPJ0bkWOUmH7MEOR77qc"
+    );
     let duration = Duration::from_millis(207959);
     assert_eq!(full_track.duration, duration);
 }
@@ -591,6 +812,361 @@ fn test_resume_point() {
     assert_eq!(resume_point.resume_position, duration);
 }
 
+/// The resume point is only included with the `user-read-playback-position`
+/// scope, so it's missing otherwise.
+#[test]
+fn test_episode_resume_point() {
+    let mut json = serde_json::json!({
+        "audio_preview_url": null,
+        "description": "",
+        "duration_ms": 2685023,
+        "explicit": false,
+        "external_urls": {},
+        "href": "https://api.spotify.com/v1/episodes/3brfPv3PaUhspkm1T9ZVl8",
+        "id": "3brfPv3PaUhspkm1T9ZVl8",
+        "images": [],
+        "is_externally_hosted": false,
+        "is_playable": true,
+        "language": "sv",
+        "languages": ["sv"],
+        "name": "På Bellmans bakgata",
+        "release_date": "2020-10-20",
+        "release_date_precision": "day",
+        "resume_point": {
+            "fully_played": true,
+            "resume_position_ms": 423432
+        },
+        "type": "episode",
+        "uri": "spotify:episode:3brfPv3PaUhspkm1T9ZVl8"
+    });
+    let episode: SimplifiedEpisode = serde_json::from_value(json.clone()).unwrap();
+    let resume_point = episode.resume_point.unwrap();
+    assert!(resume_point.fully_played);
+    assert_eq!(resume_point.resume_position, Duration::from_millis(423432));
+
+    json.as_object_mut().unwrap().remove("resume_point");
+    let episode: SimplifiedEpisode = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(episode.resume_point, None);
+
+    // The full episode also includes its show
+    json["show"] = serde_json::json!({
+        "available_markets": [],
+        "copyrights": [],
+        "description": "",
+        "explicit": false,
+        "external_urls": {},
+        "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+        "id": "38bS44xjbVVZ3No3ByF1dJ",
+        "images": [],
+        "is_externally_hosted": false,
+        "languages": ["sv"],
+        "media_type": "audio",
+        "name": "Vetenskapsradion Historia",
+        "publisher": "Sveriges Radio",
+        "type": "show",
+        "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+    });
+    let episode: FullEpisode = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(episode.resume_point, None);
+
+    json["resume_point"] = serde_json::json!({
+        "fully_played": false,
+        "resume_position_ms": 0
+    });
+    let episode: FullEpisode = serde_json::from_value(json).unwrap();
+    let resume_point = episode.resume_point.unwrap();
+    assert!(!resume_point.fully_played);
+    assert_eq!(resume_point.resume_position, Duration::from_millis(0));
+}
+
+#[test]
+fn test_recently_played() {
+    let json = r#"
+{
+  "items": [
+    {
+      "track": {
+        "album": {
+          "album_type": "single",
+          "artists": [
+            {
+              "external_urls": {
+                "spotify": "https://open.spotify.com/artist/6sFIWsNpZYqfjUpaCgueju"
+              },
+              "href": "https://api.spotify.com/v1/artists/6sFIWsNpZYqfjUpaCgueju",
+              "id": "6sFIWsNpZYqfjUpaCgueju",
+              "name": "Carly Rae Jepsen",
+              "type": "artist",
+              "uri": "spotify:artist:6sFIWsNpZYqfjUpaCgueju"
+            }
+          ],
+          "available_markets": [
+            "ZA"
+          ],
+          "external_urls": {
+            "spotify": "https://open.spotify.com/album/0tGPJ0bkWOUmH7MEOR77qc"
+          },
+          "href": "https://api.spotify.com/v1/albums/0tGPJ0bkWOUmH7MEOR77qc",
+          "id": "0tGPJ0bkWOUmH7MEOR77qc",
+          "images": [
+            {
+              "height": 64,
+              "url": "https://i.scdn.co/image/5a73a056d0af707b4119a883d87285feda543fbb",
+              "width": 64
+            }
+          ],
+          "name": "Cut To The Feeling",
+          "release_date": "2017-05-26",
+          "release_date_precision": "day",
+          "type": "album",
+          "uri": "spotify:album:0tGPJ0bkWOUmH7MEOR77qc"
+        },
+        "artists": [
+          {
+            "external_urls": {
+              "spotify": "https://open.spotify.com/artist/6sFIWsNpZYqfjUpaCgueju"
+            },
+            "href": "https://api.spotify.com/v1/artists/6sFIWsNpZYqfjUpaCgueju",
+            "id": "6sFIWsNpZYqfjUpaCgueju",
+            "name": "Carly Rae Jepsen",
+            "type": "artist",
+            "uri": "spotify:artist:6sFIWsNpZYqfjUpaCgueju"
+          }
+        ],
+        "available_markets": [
+          "ZA"
+        ],
+        "disc_number": 1,
+        "duration_ms": 207959,
+        "explicit": false,
+        "external_ids": {
+          "isrc": "USUM71703861"
+        },
+        "external_urls": {
+          "spotify": "https://open.spotify.com/track/11dFghVXANMlKmJXsNCbNl"
+        },
+        "href": "https://api.spotify.com/v1/tracks/11dFghVXANMlKmJXsNCbNl",
+        "id": "11dFghVXANMlKmJXsNCbNl",
+        "is_local": false,
+        "name": "Cut To The Feeling",
+        "popularity": 63,
+        "preview_url": "https://p.scdn.co/mp3-preview/3eb16018c2a700240e9dfb8817b6f2d041f15eb1?cid=774b29d4f13844c495f206cafdad9c86",
+        "track_number": 1,
+        "type": "track",
+        "uri": "spotify:track:11dFghVXANMlKmJXsNCbNl"
+      },
+      "played_at": "2016-12-13T20:44:04.589Z",
+      "context": {
+        "external_urls": {
+          "spotify": "https://open.spotify.com/album/0tGPJ0bkWOUmH7MEOR77qc"
+        },
+        "href": "https://api.spotify.com/v1/albums/0tGPJ0bkWOUmH7MEOR77qc",
+        "type": "album",
+        "uri": "spotify:album:0tGPJ0bkWOUmH7MEOR77qc"
+      }
+    }
+  ],
+  "next": "https://api.spotify.com/v1/me/player/recently-played?before=1481661844589&limit=1",
+  "cursors": {
+    "after": "1481661844589",
+    "before": "1481661844589"
+  },
+  "limit": 1,
+  "href": "https://api.spotify.com/v1/me/player/recently-played?limit=1"
+}
+"#;
+    let page: CursorBasedPage<PlayHistory> = serde_json::from_str(json).unwrap();
+    assert_eq!(page.items[0].track.name, "Cut To The Feeling");
+    assert_eq!(page.items[0].played_at.timestamp_millis(), 1481661844589);
+    assert_eq!(page.cursors.after.as_deref(), Some("1481661844589"));
+    assert_eq!(page.cursors.before.as_deref(), Some("1481661844589"));
+    assert_eq!(page.total, None);
+
+    // Other cursor-based endpoints only include `after`
+    let json = r#"{"after": "0I2XqVXqHScXjHhk6AYYRe"}"#;
+    let cursor: Cursor = serde_json::from_str(json).unwrap();
+    assert_eq!(cursor.after.as_deref(), Some("0I2XqVXqHScXjHhk6AYYRe"));
+    assert_eq!(cursor.before, None);
+}
+
+#[test]
+fn test_cursor_page_full_artists() {
+    let json = r#"
+{
+  "artists": {
+    "href": "https://api.spotify.com/v1/me/following?type=artist&limit=1",
+    "items": [],
+    "limit": 1,
+    "next": null,
+    "cursors": {
+      "after": null
+    },
+    "total": 0
+  }
+}
+"#;
+    let page: CursorPageFullArtists = serde_json::from_str(json).unwrap();
+    let page = page.artists;
+    assert!(page.items.is_empty());
+    assert_eq!(page.next, None);
+    assert_eq!(page.cursors.after, None);
+    assert_eq!(page.cursors.before, None);
+    assert_eq!(page.total, Some(0));
+}
+
+#[test]
+fn test_new_releases() {
+    let json = r#"
+{
+  "albums": {
+    "href": "https://api.spotify.com/v1/browse/new-releases?country=SE&offset=0&limit=1",
+    "items": [
+      {
+        "album_type": "single",
+        "artists": [
+          {
+            "external_urls": {
+              "spotify": "https://open.spotify.com/artist/2RdwBSPQiwcmiDo9kixcl8"
+            },
+            "href": "https://api.spotify.com/v1/artists/2RdwBSPQiwcmiDo9kixcl8",
+            "id": "2RdwBSPQiwcmiDo9kixcl8",
+            "name": "Pharrell Williams",
+            "type": "artist",
+            "uri": "spotify:artist:2RdwBSPQiwcmiDo9kixcl8"
+          }
+        ],
+        "available_markets": ["SE"],
+        "external_urls": {
+          "spotify": "https://open.spotify.com/album/5ZX4m5aVSmWQ5iHAPQpT71"
+        },
+        "href": "https://api.spotify.com/v1/albums/5ZX4m5aVSmWQ5iHAPQpT71",
+        "id": "5ZX4m5aVSmWQ5iHAPQpT71",
+        "images": [],
+        "name": "Runnin'",
+        "release_date": "2016-12-09",
+        "release_date_precision": "day",
+        "type": "album",
+        "uri": "spotify:album:5ZX4m5aVSmWQ5iHAPQpT71"
+      }
+    ],
+    "limit": 1,
+    "next": "https://api.spotify.com/v1/browse/new-releases?country=SE&offset=1&limit=1",
+    "offset": 0,
+    "previous": null,
+    "total": 500
+  }
+}
+"#;
+    let page = serde_json::from_str::<PageSimpliedAlbums>(json)
+        .unwrap()
+        .albums;
+    assert_eq!(page.total, 500);
+    assert_eq!(page.items.len(), 1);
+    let album = &page.items[0];
+    assert_eq!(album.name, "Runnin'");
+    assert_eq!(album.album_type.as_deref(), Some("single"));
+    assert_eq!(album.artists[0].name, "Pharrell Williams");
+    assert_eq!(album.available_markets, [Country::Sweden]);
+}
+
+#[test]
+fn test_categories() {
+    let json = r#"
+{
+  "categories": {
+    "href": "https://api.spotify.com/v1/browse/categories?offset=0&limit=1",
+    "items": [
+      {
+        "href": "https://api.spotify.com/v1/browse/categories/toplists",
+        "icons": [
+          {
+            "height": 275,
+            "url": "https://datsnxq1rwndn.cloudfront.net/media/derived/toplists_11160599e6a04ac5d6f2757f5511778f_0_0_275_275.jpg",
+            "width": 275
+          }
+        ],
+        "id": "toplists",
+        "name": "Top Lists"
+      }
+    ],
+    "limit": 1,
+    "next": "https://api.spotify.com/v1/browse/categories?offset=1&limit=1",
+    "offset": 0,
+    "previous": null,
+    "total": 31
+  }
+}
+"#;
+    let page = serde_json::from_str::<PageCategory>(json)
+        .unwrap()
+        .categories;
+    assert_eq!(page.total, 31);
+    let category = &page.items[0];
+    assert_eq!(category.id, "toplists");
+    assert_eq!(category.name, "Top Lists");
+    assert_eq!(
+        category.href,
+        "https://api.spotify.com/v1/browse/categories/toplists"
+    );
+    assert_eq!(category.icons[0].height, Some(275));
+    assert_eq!(category.icons[0].width, Some(275));
+}
+
+#[test]
+fn test_category_playlists() {
+    let json = r#"
+{
+  "playlists": {
+    "href": "https://api.spotify.com/v1/browse/categories/party/playlists?offset=0&limit=1",
+    "items": [
+      {
+        "collaborative": false,
+        "external_urls": {
+          "spotify": "https://open.spotify.com/playlist/37i9dQZF1DXaXB8fQg7xif"
+        },
+        "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DXaXB8fQg7xif",
+        "id": "37i9dQZF1DXaXB8fQg7xif",
+        "images": [],
+        "name": "Dance Party",
+        "owner": {
+          "display_name": "Spotify",
+          "external_urls": {
+            "spotify": "https://open.spotify.com/user/spotify"
+          },
+          "href": "https://api.spotify.com/v1/users/spotify",
+          "id": "spotify",
+          "type": "user",
+          "uri": "spotify:user:spotify"
+        },
+        "public": null,
+        "snapshot_id": "MTU2NzM4MjQ3NiwwMDAwMDAwMA==",
+        "tracks": {
+          "href": "https://api.spotify.com/v1/playlists/37i9dQZF1DXaXB8fQg7xif/tracks",
+          "total": 150
+        },
+        "type": "playlist",
+        "uri": "spotify:playlist:37i9dQZF1DXaXB8fQg7xif"
+      }
+    ],
+    "limit": 1,
+    "next": null,
+    "offset": 0,
+    "previous": null,
+    "total": 1
+  }
+}
+"#;
+    let page = serde_json::from_str::<CategoryPlaylists>(json)
+        .unwrap()
+        .playlists;
+    assert_eq!(page.total, 1);
+    let playlist = &page.items[0];
+    assert_eq!(playlist.name, "Dance Party");
+    assert_eq!(playlist.owner.id, "spotify");
+    assert_eq!(playlist.tracks.total, 150);
+    assert_eq!(playlist.public, None);
+}
+
 #[test]
 fn test_currently_playing_context() {
     let json = r#"
@@ -816,6 +1392,8 @@ fn test_current_playback_context() {
     );
     assert_eq!(current_playback_context.timestamp, dt);
     assert!(current_playback_context.progress.is_none());
+    assert_eq!(current_playback_context.repeat_state, RepeatState::Off);
+    assert!(!current_playback_context.shuffle_state);
 }
 
 #[test]
@@ -901,3 +1479,784 @@ fn test_simplified_playlist() {
     );
     assert_eq!(simplified_playlist.tracks.total, 62);
 }
+
+#[test]
+fn test_api_error() {
+    let json = r#"
+{
+  "error": {
+    "status": 404,
+    "message": "Non existing id: 'spotify:track:abcdef'"
+  }
+}
+"#;
+    let error: ApiError = serde_json::from_str(json).unwrap();
+    assert!(matches!(error, ApiError::Regular { status: 404, .. }));
+    assert_eq!(error.status(), Some(404));
+
+    let json = r#"
+{
+  "error": {
+    "status": 403,
+    "message": "Player command failed: Premium required",
+    "reason": "PREMIUM_REQUIRED"
+  }
+}
+"#;
+    let error: ApiError = serde_json::from_str(json).unwrap();
+    match error {
+        ApiError::Player { status, reason, .. } => {
+            assert_eq!(status, 403);
+            assert_eq!(reason, "PREMIUM_REQUIRED");
+        }
+        _ => panic!("unexpected error: {:?}", error),
+    }
+
+    let json = r#"
+{
+  "error": "invalid_client",
+  "error_description": "Invalid client secret"
+}
+"#;
+    let error: ApiError = serde_json::from_str(json).unwrap();
+    match error {
+        ApiError::Auth {
+            error,
+            error_description,
+        } => {
+            assert_eq!(error, "invalid_client");
+            assert_eq!(error_description, "Invalid client secret");
+        }
+        _ => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_markets() {
+    let json = r#"
+{
+  "markets": ["AD", "AE", "AG", "XK", "ZW"]
+}
+"#;
+    let markets: Markets = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        markets.markets,
+        vec![
+            Market::Country(Country::Andorra),
+            Market::Country(Country::UnitedArabEmirates),
+            Market::Country(Country::AntiguaAndBarbuda),
+            Market::Country(Country::Kosovo),
+            Market::Country(Country::Zimbabwe),
+        ]
+    );
+}
+
+#[test]
+fn test_market_serialization() {
+    let market: Market = serde_json::from_str(r#""from_token""#).unwrap();
+    assert_eq!(market, Market::FromToken);
+    assert_eq!(market.as_ref(), "from_token");
+    assert_eq!(serde_json::to_string(&market).unwrap(), r#""from_token""#);
+
+    let market = Market::Country(Country::Sweden);
+    assert_eq!(market.as_ref(), "SE");
+    assert_eq!(serde_json::to_string(&market).unwrap(), r#""SE""#);
+}
+
+#[test]
+fn test_current_user_queue() {
+    let track = r#"
+    {
+  "album": {
+    "album_type": "single",
+    "artists": [
+      {
+        "external_urls": {
+          "spotify": "https://open.spotify.com/artist/6sFIWsNpZYqfjUpaCgueju"
+        },
+        "href": "https://api.spotify.com/v1/artists/6sFIWsNpZYqfjUpaCgueju",
+        "id": "6sFIWsNpZYqfjUpaCgueju",
+        "name": "Carly Rae Jepsen",
+        "type": "artist",
+        "uri": "spotify:artist:6sFIWsNpZYqfjUpaCgueju"
+      }
+    ],
+    "available_markets": [
+      "ZA"
+    ],
+    "external_urls": {
+      "spotify": "https://open.spotify.com/album/0tGPJ0bkWOUmH7MEOR77qc"
+    },
+    "href": "https://api.spotify.com/v1/albums/0tGPJ0bkWOUmH7MEOR77qc",
+    "id": "0tGPJ0bkWOUmH7MEOR77qc",
+    "images": [
+      {
+        "height": 64,
+        "url": "https://i.scdn.co/image/5a73a056d0af707b4119a883d87285feda543fbb",
+        "width": 64
+      }
+    ],
+    "name": "Cut To The Feeling",
+    "release_date": "2017-05-26",
+    "release_date_precision": "day",
+    "type": "album",
+    "uri": "spotify:album:0tGPJ0bkWOUmH7MEOR77qc"
+  },
+  "artists": [
+    {
+      "external_urls": {
+        "spotify": "https://open.spotify.com/artist/6sFIWsNpZYqfjUpaCgueju"
+      },
+      "href": "https://api.spotify.com/v1/artists/6sFIWsNpZYqfjUpaCgueju",
+      "id": "6sFIWsNpZYqfjUpaCgueju",
+      "name": "Carly Rae Jepsen",
+      "type": "artist",
+      "uri": "spotify:artist:6sFIWsNpZYqfjUpaCgueju"
+    }
+  ],
+  "available_markets": [
+    "ZA"
+  ],
+  "disc_number": 1,
+  "duration_ms": 207959,
+  "explicit": false,
+  "external_ids": {
+    "isrc": "USUM71703861"
+  },
+  "external_urls": {
+    "spotify": "https://open.spotify.com/track/11dFghVXANMlKmJXsNCbNl"
+  },
+  "href": "https://api.spotify.com/v1/tracks/11dFghVXANMlKmJXsNCbNl",
+  "id": "11dFghVXANMlKmJXsNCbNl",
+  "is_local": false,
+  "name": "Cut To The Feeling",
+  "popularity": 63,
+  "preview_url": "https://p.scdn.co/mp3-preview/3eb16018c2a700240e9dfb8817b6f2d041f15eb1?cid=774b29d4f13844c495f206cafdad9c86",
+  "track_number": 1,
+  "type": "track",
+  "uri": "spotify:track:11dFghVXANMlKmJXsNCbNl"
+}
+"#;
+    let episode = r#"
+    {
+        "audio_preview_url": "https://p.scdn.co/mp3-preview/566fcc94708f39bcddc09e4ce84a8e5db8f07d4d",
+        "description": "En ny tysk ",
+        "duration_ms": 1502795,
+        "explicit": false,
+        "external_urls": {
+            "spotify": "https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ"
+        },
+        "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+        "id": "512ojhOuo1ktJprKbVcKyQ",
+        "images": [
+            {
+                "height": 64,
+                "url": "https://i.scdn.co/image/e29c75799cad73927fad713011edad574868d8da",
+                "width": 64
+            }
+        ],
+        "is_externally_hosted": false,
+        "is_playable": true,
+        "language": "sv",
+        "languages": [
+            "sv"
+        ],
+        "name": "Tredje rikets knarkande granskas",
+        "release_date": "2015-10-01",
+        "release_date_precision": "day",
+        "show": {
+            "available_markets": [
+                "ZA"
+            ],
+            "copyrights": [],
+            "description": "Vi är där historien är. Ansvarig utgivare: Nina Glans",
+            "explicit": false,
+            "external_urls": {
+                "spotify": "https://open.spotify.com/show/38bS44xjbVVZ3No3ByF1dJ"
+            },
+            "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+            "id": "38bS44xjbVVZ3No3ByF1dJ",
+            "images": [
+                {
+                    "height": 64,
+                    "url": "https://i.scdn.co/image/3dc007829bc0663c24089e46743a9f4ae15e65f8",
+                    "width": 64
+                }
+            ],
+            "is_externally_hosted": false,
+            "languages": [
+                "sv"
+            ],
+            "media_type": "audio",
+            "name": "Vetenskapsradion Historia",
+            "publisher": "Sveriges Radio",
+            "type": "show",
+            "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+        },
+        "type": "episode",
+        "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+    }
+"#;
+    let json = format!(
+        r#"{{"currently_playing": {0}, "queue": [{1}, {0}]}}"#,
+        track, episode
+    );
+    let queue: CurrentUserQueue = serde_json::from_str(&json).unwrap();
+    match queue.currently_playing {
+        Some(PlayableItem::Track(track)) => assert_eq!(track.name, "Cut To The Feeling"),
+        item => panic!("unexpected item: {:?}", item),
+    }
+    assert_eq!(queue.queue.len(), 2);
+    match &queue.queue[0] {
+        PlayableItem::Episode(episode) => {
+            assert_eq!(episode.name, "Tredje rikets knarkande granskas")
+        }
+        item => panic!("unexpected item: {:?}", item),
+    }
+    assert!(matches!(queue.queue[1], PlayableItem::Track(_)));
+
+    let json = r#"{"currently_playing": null, "queue": []}"#;
+    let queue: CurrentUserQueue = serde_json::from_str(json).unwrap();
+    assert_eq!(queue.currently_playing, None);
+    assert!(queue.queue.is_empty());
+}
+
+#[test]
+fn test_search_multiple_result() {
+    let json = r#"
+{
+  "shows": {
+    "href": "https://api.spotify.com/v1/search?query=rust&type=show%2Cepisode&offset=0&limit=1",
+    "items": [
+      {
+        "available_markets": ["US"],
+        "copyrights": [],
+        "description": "A podcast about the Rust programming language",
+        "explicit": false,
+        "external_urls": {
+          "spotify": "https://open.spotify.com/show/4rOoJ6Egrf8K2IrywzwOMk"
+        },
+        "href": "https://api.spotify.com/v1/shows/4rOoJ6Egrf8K2IrywzwOMk",
+        "id": "4rOoJ6Egrf8K2IrywzwOMk",
+        "images": [],
+        "is_externally_hosted": false,
+        "languages": ["en"],
+        "media_type": "audio",
+        "name": "Rustacean Station",
+        "publisher": "Rustacean Station",
+        "type": "show",
+        "uri": "spotify:show:4rOoJ6Egrf8K2IrywzwOMk"
+      }
+    ],
+    "limit": 1,
+    "next": null,
+    "offset": 0,
+    "previous": null,
+    "total": 1
+  },
+  "episodes": {
+    "href": "https://api.spotify.com/v1/search?query=rust&type=show%2Cepisode&offset=0&limit=1",
+    "items": [
+      {
+        "audio_preview_url": null,
+        "description": "Talking about async Rust",
+        "duration_ms": 2685023,
+        "explicit": false,
+        "external_urls": {
+          "spotify": "https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ"
+        },
+        "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+        "id": "512ojhOuo1ktJprKbVcKyQ",
+        "images": [],
+        "is_externally_hosted": false,
+        "is_playable": true,
+        "language": "en",
+        "languages": ["en"],
+        "name": "Async Rust",
+        "release_date": "2021-03-01",
+        "release_date_precision": "day",
+        "type": "episode",
+        "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+      }
+    ],
+    "limit": 1,
+    "next": null,
+    "offset": 0,
+    "previous": null,
+    "total": 1
+  }
+}
+"#;
+    let result: SearchMultipleResult = serde_json::from_str(json).unwrap();
+    assert_eq!(result.shows.unwrap().items[0].name, "Rustacean Station");
+    assert_eq!(result.episodes.unwrap().items[0].name, "Async Rust");
+    assert!(result.tracks.is_none());
+    assert!(result.audiobooks.is_none());
+}
+
+#[test]
+fn test_search_audiobooks() {
+    let json = r#"
+{
+  "audiobooks": {
+    "href": "https://api.spotify.com/v1/search?query=rust&type=audiobook&offset=0&limit=1",
+    "items": [
+      {
+        "authors": [{"name": "Steve Klabnik"}, {"name": "Carol Nichols"}],
+        "available_markets": ["US"],
+        "copyrights": [],
+        "description": "The official book on the Rust programming language",
+        "edition": "Unabridged",
+        "explicit": false,
+        "external_urls": {
+          "spotify": "https://open.spotify.com/show/7iHfbu1YPACw6oZPAFJtqe"
+        },
+        "href": "https://api.spotify.com/v1/audiobooks/7iHfbu1YPACw6oZPAFJtqe",
+        "id": "7iHfbu1YPACw6oZPAFJtqe",
+        "images": [],
+        "languages": ["English"],
+        "media_type": "audio",
+        "name": "The Rust Programming Language",
+        "narrators": [{"name": "Ferris"}],
+        "publisher": "No Starch Press",
+        "total_chapters": 20,
+        "type": "audiobook",
+        "uri": "spotify:show:7iHfbu1YPACw6oZPAFJtqe"
+      }
+    ],
+    "limit": 1,
+    "next": null,
+    "offset": 0,
+    "previous": null,
+    "total": 1
+  }
+}
+"#;
+    let result: SearchResult = serde_json::from_str(json).unwrap();
+    match result {
+        SearchResult::Audiobooks(page) => {
+            let audiobook = &page.items[0];
+            assert_eq!(audiobook.authors.len(), 2);
+            assert_eq!(audiobook.narrators[0].name, "Ferris");
+            assert_eq!(audiobook.total_chapters, Some(20));
+        }
+        _ => panic!("unexpected search result: {:?}", result),
+    }
+}
+
+#[test]
+fn test_saved_audiobooks() {
+    let json = r#"
+{
+  "href": "https://api.spotify.com/v1/me/audiobooks?offset=0&limit=1",
+  "items": [
+    {
+      "authors": [{"name": "Jane Austen"}],
+      "available_markets": ["GB", "US"],
+      "copyrights": [],
+      "description": "A classic novel",
+      "edition": null,
+      "explicit": false,
+      "external_urls": {
+        "spotify": "https://open.spotify.com/show/1HGw3J3NxZO1TP1BTtVhpZ"
+      },
+      "href": "https://api.spotify.com/v1/audiobooks/1HGw3J3NxZO1TP1BTtVhpZ",
+      "id": "1HGw3J3NxZO1TP1BTtVhpZ",
+      "images": [
+        {
+          "height": 640,
+          "url": "https://i.scdn.co/image/ab676663000022a8f5a8c8bb1f9e3c39b1c61a4f",
+          "width": 640
+        }
+      ],
+      "languages": ["English"],
+      "media_type": "audio",
+      "name": "Pride and Prejudice",
+      "narrators": [{"name": "Rosamund Pike"}],
+      "publisher": "Audible Studios",
+      "total_chapters": 61,
+      "type": "audiobook",
+      "uri": "spotify:show:1HGw3J3NxZO1TP1BTtVhpZ"
+    }
+  ],
+  "limit": 1,
+  "next": "https://api.spotify.com/v1/me/audiobooks?offset=1&limit=1",
+  "offset": 0,
+  "previous": null,
+  "total": 2
+}
+"#;
+    let page: Page<SimplifiedAudiobook> = serde_json::from_str(json).unwrap();
+    assert_eq!(page.total, 2);
+    let audiobook = &page.items[0];
+    assert_eq!(audiobook.name, "Pride and Prejudice");
+    assert_eq!(audiobook.authors[0].name, "Jane Austen");
+    assert_eq!(audiobook.narrators[0].name, "Rosamund Pike");
+    assert_eq!(audiobook.edition, None);
+    assert_eq!(
+        audiobook.available_markets,
+        [Country::UnitedKingdom, Country::UnitedStates]
+    );
+    assert_eq!(
+        audiobook.id().unwrap(),
+        AudiobookId::from_id("1HGw3J3NxZO1TP1BTtVhpZ").unwrap()
+    );
+
+    let audiobook_type: Type = serde_json::from_str(r#""audiobook""#).unwrap();
+    assert_eq!(audiobook_type, Type::Audiobook);
+}
+
+#[test]
+fn test_playable_item() {
+    let track = r#"
+{
+  "album": {
+    "album_type": "single",
+    "artists": [],
+    "available_markets": [],
+    "external_urls": {},
+    "href": "https://api.spotify.com/v1/albums/6cunQwT9nfo8gxrmO3ag5T",
+    "id": "6cunQwT9nfo8gxrmO3ag5T",
+    "images": [],
+    "name": "Cut To The Feeling",
+    "release_date": "2017-05-26",
+    "release_date_precision": "day",
+    "type": "album",
+    "uri": "spotify:album:6cunQwT9nfo8gxrmO3ag5T"
+  },
+  "artists": [],
+  "disc_number": 1,
+  "duration_ms": 207959,
+  "explicit": false,
+  "external_ids": {},
+  "external_urls": {},
+  "href": "https://api.spotify.com/v1/tracks/6a8QOEZSDhUHPXrIL1hrYu",
+  "id": "6a8QOEZSDhUHPXrIL1hrYu",
+  "is_local": false,
+  "name": "Cut To The Feeling",
+  "popularity": 63,
+  "preview_url": null,
+  "track_number": 1,
+  "type": "track",
+  "uri": "spotify:track:6a8QOEZSDhUHPXrIL1hrYu"
+}
+"#;
+    let item: PlayableItem = serde_json::from_str(track).unwrap();
+    assert!(matches!(item, PlayableItem::Track(_)));
+    let json = serde_json::to_value(&item).unwrap();
+    assert_eq!(json["type"], "track");
+    assert_eq!(serde_json::from_value::<PlayableItem>(json).unwrap(), item);
+
+    let episode = r#"
+{
+  "audio_preview_url": null,
+  "description": "Talking about async Rust",
+  "duration_ms": 2685023,
+  "explicit": false,
+  "external_urls": {},
+  "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+  "id": "512ojhOuo1ktJprKbVcKyQ",
+  "images": [],
+  "is_externally_hosted": false,
+  "is_playable": true,
+  "language": "en",
+  "languages": ["en"],
+  "name": "Async Rust",
+  "release_date": "2021-03-01",
+  "release_date_precision": "day",
+  "resume_point": null,
+  "show": {
+    "available_markets": [],
+    "copyrights": [],
+    "description": "A podcast about the Rust programming language",
+    "explicit": false,
+    "external_urls": {},
+    "href": "https://api.spotify.com/v1/shows/4rOoJ6Egrf8K2IrywzwOMk",
+    "id": "4rOoJ6Egrf8K2IrywzwOMk",
+    "images": [],
+    "is_externally_hosted": false,
+    "languages": ["en"],
+    "media_type": "audio",
+    "name": "Rustacean Station",
+    "publisher": "Rustacean Station",
+    "type": "show",
+    "uri": "spotify:show:4rOoJ6Egrf8K2IrywzwOMk"
+  },
+  "type": "episode",
+  "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+}
+"#;
+    let item: PlayableItem = serde_json::from_str(episode).unwrap();
+    assert!(matches!(item, PlayableItem::Episode(_)));
+    let json = serde_json::to_value(&item).unwrap();
+    assert_eq!(json["type"], "episode");
+    assert_eq!(serde_json::from_value::<PlayableItem>(json).unwrap(), item);
+
+    // Unknown types aren't silently parsed as something else
+    let json = track.replace(r#""type": "track""#, r#""type": "ad""#);
+    assert!(serde_json::from_str::<PlayableItem>(&json).is_err());
+}
+
+#[test]
+fn test_currently_playing_without_item() {
+    let json = r#"
+{
+  "context": null,
+  "timestamp": 1607769168429,
+  "progress_ms": null,
+  "is_playing": false,
+  "item": null,
+  "currently_playing_type": "ad",
+  "actions": {
+    "disallows": {}
+  }
+}
+"#;
+    let context: CurrentlyPlayingContext = serde_json::from_str(json).unwrap();
+    assert_eq!(context.item, None);
+
+    let json = serde_json::to_string(&context.item).unwrap();
+    assert_eq!(json, "null");
+    let item: Option<PlayableItem> = serde_json::from_str(&json).unwrap();
+    assert_eq!(item, None);
+}
+
+#[test]
+fn test_playlist_result() {
+    let json =
+        r#"{"snapshot_id": "JbtmHBDBAYu3/bt8BOXKjzKx3i0b6LCa/wVjyl6qQ2Yf6nFXkbmzuEa+ZI/U1yF+"}"#;
+    let result: PlaylistResult = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        result.snapshot_id,
+        "JbtmHBDBAYu3/bt8BOXKjzKx3i0b6LCa/wVjyl6qQ2Yf6nFXkbmzuEa+ZI/U1yF+"
+    );
+
+    let json = serde_json::to_string(&result).unwrap();
+    assert_eq!(
+        serde_json::from_str::<PlaylistResult>(&json).unwrap(),
+        result
+    );
+}
+
+#[test]
+fn test_time_range() {
+    let ranges = [
+        (TimeRange::LongTerm, "long_term"),
+        (TimeRange::MediumTerm, "medium_term"),
+        (TimeRange::ShortTerm, "short_term"),
+    ];
+    for (range, value) in ranges.iter() {
+        assert_eq!(range.as_ref(), *value);
+        let json = serde_json::to_string(range).unwrap();
+        assert_eq!(json, format!("\"{}\"", value));
+        assert_eq!(serde_json::from_str::<TimeRange>(&json).unwrap(), *range);
+    }
+}
+
+#[test]
+fn test_saved_show() {
+    let json = r#"
+{
+  "added_at": "2020-11-18T12:04:17Z",
+  "show": {
+    "available_markets": ["SE", "US"],
+    "copyrights": [],
+    "description": "Vetenskapsradion Historia",
+    "explicit": false,
+    "external_urls": {
+      "spotify": "https://open.spotify.com/show/38bS44xjbVVZ3No3ByF1dJ"
+    },
+    "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+    "id": "38bS44xjbVVZ3No3ByF1dJ",
+    "images": [],
+    "is_externally_hosted": false,
+    "languages": ["sv"],
+    "media_type": "audio",
+    "name": "Vetenskapsradion Historia",
+    "publisher": "Sveriges Radio",
+    "type": "show",
+    "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+  }
+}
+"#;
+    let saved: Show = serde_json::from_str(json).unwrap();
+    assert_eq!(saved.added_at, "2020-11-18T12:04:17Z");
+    assert_eq!(saved.show.name, "Vetenskapsradion Historia");
+    assert_eq!(saved.show.publisher, "Sveriges Radio");
+    assert_eq!(saved.show.is_externally_hosted, Some(false));
+    assert_eq!(
+        saved.show.available_markets,
+        [Country::Sweden, Country::UnitedStates]
+    );
+    assert_eq!(
+        saved.show.id().unwrap(),
+        ShowId::from_id("38bS44xjbVVZ3No3ByF1dJ").unwrap()
+    );
+}
+
+#[test]
+fn test_several_shows_and_episodes() {
+    let show = serde_json::json!({
+        "available_markets": ["US"],
+        "copyrights": [],
+        "description": "A show",
+        "explicit": false,
+        "external_urls": {
+            "spotify": "https://open.spotify.com/show/38bS44xjbVVZ3No3ByF1dJ"
+        },
+        "href": "https://api.spotify.com/v1/shows/38bS44xjbVVZ3No3ByF1dJ",
+        "id": "38bS44xjbVVZ3No3ByF1dJ",
+        "images": [],
+        "is_externally_hosted": false,
+        "languages": ["sv"],
+        "media_type": "audio",
+        "name": "Vetenskapsradion Historia",
+        "publisher": "Sveriges Radio",
+        "type": "show",
+        "uri": "spotify:show:38bS44xjbVVZ3No3ByF1dJ"
+    });
+    let episode = serde_json::json!({
+        "audio_preview_url": null,
+        "description": "An episode",
+        "duration_ms": 1502795,
+        "explicit": false,
+        "external_urls": {
+            "spotify": "https://open.spotify.com/episode/512ojhOuo1ktJprKbVcKyQ"
+        },
+        "href": "https://api.spotify.com/v1/episodes/512ojhOuo1ktJprKbVcKyQ",
+        "id": "512ojhOuo1ktJprKbVcKyQ",
+        "images": [],
+        "is_externally_hosted": false,
+        "is_playable": true,
+        "language": "sv",
+        "languages": ["sv"],
+        "name": "Tredje rikets knarkande granskas",
+        "release_date": "2015-10-01",
+        "release_date_precision": "day",
+        "show": show,
+        "type": "episode",
+        "uri": "spotify:episode:512ojhOuo1ktJprKbVcKyQ"
+    });
+
+    // Unavailable items are returned as `null`
+    let json = serde_json::json!({ "shows": [show, null] });
+    let shows: SeversalSimplifiedShows = serde_json::from_value(json).unwrap();
+    assert_eq!(shows.shows.len(), 2);
+    assert_eq!(
+        shows.shows[0].as_ref().unwrap().name,
+        "Vetenskapsradion Historia"
+    );
+    assert!(shows.shows[1].is_none());
+
+    let json = serde_json::json!({ "episodes": [null, episode] });
+    let episodes: EpisodesPayload = serde_json::from_value(json).unwrap();
+    assert!(episodes.episodes[0].is_none());
+    assert_eq!(
+        episodes.episodes[1].as_ref().unwrap().id,
+        "512ojhOuo1ktJprKbVcKyQ"
+    );
+}
+
+#[test]
+fn test_followers() {
+    for json in [
+        r#"{"href": null, "total": 42}"#,
+        r#"{"total": 42}"#,
+        r#"{"href": "https://api.spotify.com/v1/followers", "total": 42}"#,
+    ] {
+        let followers: Followers = serde_json::from_str(json).unwrap();
+        assert_eq!(followers, Followers { total: 42 });
+    }
+}
+
+#[test]
+fn test_page_filter_items() {
+    let track = |name: &str, explicit: bool| {
+        serde_json::json!({
+            "artists": [],
+            "available_markets": null,
+            "disc_number": 1,
+            "duration_ms": 1000,
+            "explicit": explicit,
+            "external_urls": {},
+            "href": null,
+            "id": null,
+            "is_local": false,
+            "is_playable": null,
+            "linked_from": null,
+            "restrictions": null,
+            "name": name,
+            "preview_url": null,
+            "track_number": 1,
+            "type": "track",
+            "uri": "spotify:track:4iV5W9uYEdYUVa79Axb7Rh"
+        })
+    };
+    let json = serde_json::json!({
+        "href": "https://api.spotify.com/v1/albums/0sNOF9WDwhWunNAHPD3Baj/tracks",
+        "items": [track("clean", false), track("explicit", true), track("also clean", false)],
+        "limit": 3,
+        "next": null,
+        "offset": 0,
+        "previous": null,
+        "total": 3
+    });
+    let page: Page<SimplifiedTrack> = serde_json::from_value(json).unwrap();
+
+    let clean = page.filter_items(|track| !track.explicit);
+    let names = clean.iter().map(|t| t.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["clean", "also clean"]);
+    assert_eq!(page.items.len(), 3);
+}
+
+#[test]
+fn test_available_markets() {
+    let json = r#"
+{
+    "album_type": "album",
+    "artists": [],
+    "available_markets": ["AD", "XK", "ZZ"],
+    "external_urls": {},
+    "href": null,
+    "id": "0tGPJ0bkWOUmH7MEOR77qc",
+    "images": [],
+    "name": "Emotion",
+    "type": "album",
+    "uri": null
+}
+"#;
+    let album: SimplifiedAlbum = serde_json::from_str(json).unwrap();
+    // Unknown country codes don't make the parsing fail
+    assert_eq!(
+        album.available_markets,
+        [
+            Country::Andorra,
+            Country::Kosovo,
+            Country::Unknown("ZZ".to_owned())
+        ]
+    );
+
+    let market: Market = serde_json::from_str(r#""ZZ""#).unwrap();
+    assert_eq!(market, Market::Country(Country::Unknown("ZZ".to_owned())));
+}
+
+#[test]
+fn test_unknown_type() {
+    let json = r#"
+{
+    "external_urls": {},
+    "href": null,
+    "id": null,
+    "name": "Some new object",
+    "type": "audiobook_author",
+    "uri": null
+}
+"#;
+    let artist: SimplifiedArtist = serde_json::from_str(json).unwrap();
+    assert_eq!(artist._type, Type::Unknown);
+    // Objects without an id don't have a typed one either
+    assert!(artist.id().is_none());
+
+    let known: Type = serde_json::from_str(r#""show""#).unwrap();
+    assert_eq!(known, Type::Show);
+}
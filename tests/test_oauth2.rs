@@ -1,9 +1,10 @@
 use chrono::prelude::*;
 use chrono::Duration;
 use rspotify::{
-    prelude::*, scopes, AuthCodeSpotify, ClientCredsSpotify, Config, Credentials, OAuth, Token,
+    prelude::*, scopes, AuthCodePkceSpotify, AuthCodeSpotify, CacheManager, ClientCredsSpotify,
+    ClientError, ClientResult, Config, Credentials, MemoryCacheManager, OAuth, Token,
 };
-use std::{collections::HashMap, fs, io::Read, path::PathBuf, thread::sleep};
+use std::{collections::HashMap, fs, io::Read, path::PathBuf, sync::Arc, thread::sleep};
 use url::Url;
 
 #[test]
@@ -34,13 +35,13 @@ fn test_get_authorize_url() {
 
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
 async fn test_read_token_cache() {
-    let now = Utc::now();
+    let expires_at = Utc::now() + Duration::seconds(3600);
     let scopes = scopes!("playlist-read-private", "playlist-read-collaborative");
 
     let tok = Token {
         access_token: "test-access_token".to_owned(),
         expires_in: Duration::seconds(3600),
-        expires_at: Some(now),
+        expires_at: Some(expires_at),
         scopes: scopes.clone(),
         refresh_token: Some("...".to_owned()),
     };
@@ -65,12 +66,40 @@ async fn test_read_token_cache() {
     assert_eq!(tok_from_file.scopes, scopes);
     assert_eq!(tok_from_file.refresh_token.unwrap(), "...");
     assert_eq!(tok_from_file.expires_in, Duration::seconds(3600));
-    assert_eq!(tok_from_file.expires_at.unwrap(), now);
+    assert_eq!(tok_from_file.expires_at.unwrap(), expires_at);
 
     // delete cache file in the end
     fs::remove_file(&spotify.config.cache_path).unwrap();
 }
 
+#[test]
+fn test_remove_token_cache() {
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_in: Duration::seconds(3600),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        ..Default::default()
+    };
+
+    let config = Config {
+        token_cached: true,
+        cache_path: PathBuf::from(".test_remove_token_cache.json"),
+        ..Default::default()
+    };
+    let mut spotify = ClientCredsSpotify::from_token(tok);
+    spotify.config = config;
+
+    spotify.write_token_cache().unwrap();
+    assert!(spotify.config.cache_path.exists());
+
+    spotify.remove_token_cache().unwrap();
+    assert!(!spotify.config.cache_path.exists());
+    assert!(spotify.token.is_none());
+
+    // Removing it again isn't an error
+    spotify.remove_token_cache().unwrap();
+}
+
 #[test]
 fn test_write_token() {
     let now = Utc::now();
@@ -109,6 +138,170 @@ fn test_write_token() {
     fs::remove_file(&spotify.config.cache_path).unwrap();
 }
 
+#[test]
+fn test_memory_cache_manager() {
+    let manager = MemoryCacheManager::default();
+    assert!(manager.read().unwrap().is_none());
+
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        ..Default::default()
+    };
+    manager.write(&tok).unwrap();
+    assert_eq!(
+        manager.read().unwrap().unwrap().access_token,
+        "test-access_token"
+    );
+
+    manager.remove().unwrap();
+    assert!(manager.read().unwrap().is_none());
+    // Removing it again doesn't fail
+    manager.remove().unwrap();
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_custom_cache_manager() {
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_in: Duration::seconds(3600),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        ..Default::default()
+    };
+
+    let manager = Arc::new(MemoryCacheManager::default());
+    let config = Config {
+        token_cached: true,
+        cache_path: PathBuf::from(".test_custom_cache_manager.json"),
+        cache_manager: Some(manager.clone()),
+        ..Default::default()
+    };
+    let mut predefined_spotify = ClientCredsSpotify::from_token(tok);
+    predefined_spotify.config = config.clone();
+
+    // The token is written to the custom cache instead of the file
+    predefined_spotify.write_token_cache().unwrap();
+    assert!(!config.cache_path.exists());
+    assert_eq!(
+        manager.read().unwrap().unwrap().access_token,
+        "test-access_token"
+    );
+
+    let mut spotify = ClientCredsSpotify::default();
+    spotify.config = config;
+
+    let tok_from_cache = spotify.read_token_cache().await.unwrap();
+    assert_eq!(tok_from_cache.access_token, "test-access_token");
+}
+
+/// A cache manager that fails the test if it's accessed at all.
+#[derive(Debug)]
+struct UnreachableCacheManager;
+
+impl CacheManager for UnreachableCacheManager {
+    fn read(&self) -> ClientResult<Option<Token>> {
+        panic!("the cache was read")
+    }
+
+    fn write(&self, _token: &Token) -> ClientResult<()> {
+        panic!("the cache was written")
+    }
+
+    fn remove(&self) -> ClientResult<()> {
+        panic!("the cache was removed")
+    }
+}
+
+#[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
+async fn test_token_cache_disabled() {
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_in: Duration::seconds(3600),
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        ..Default::default()
+    };
+    let config = Config {
+        token_cached: false,
+        cache_manager: Some(Arc::new(UnreachableCacheManager)),
+        ..Default::default()
+    };
+
+    let mut spotify = AuthCodeSpotify::from_token(tok.clone());
+    spotify.config = config.clone();
+    let tok_from_cache = spotify.read_token_cache().await;
+    assert!(tok_from_cache.is_none());
+    spotify.write_token_cache().unwrap();
+    spotify.remove_token_cache().unwrap();
+
+    let mut spotify = ClientCredsSpotify::from_token(tok.clone());
+    spotify.config = config;
+    let tok_from_cache = spotify.read_token_cache().await;
+    assert!(tok_from_cache.is_none());
+    spotify.write_token_cache().unwrap();
+    spotify.remove_token_cache().unwrap();
+
+    // The default file cache isn't touched either, even if its directory
+    // doesn't exist
+    let config = Config {
+        cache_path: PathBuf::from(".test_token_cache_disabled/token.json"),
+        ..Default::default()
+    };
+    let mut spotify = ClientCredsSpotify::from_token(tok);
+    spotify.config = config;
+    spotify.write_token_cache().unwrap();
+    let tok_from_cache = spotify.read_token_cache().await;
+    assert!(tok_from_cache.is_none());
+    assert!(!spotify.config.cache_path.exists());
+}
+
+#[test]
+fn test_from_token_with_config() {
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        ..Default::default()
+    };
+    let creds = Credentials::new("this-is-my-client-id", "this-is-my-client-secret");
+    let oauth = OAuth {
+        redirect_uri: "localhost".to_owned(),
+        ..Default::default()
+    };
+    let config = Config {
+        prefix: "http://localhost:8080/v1/".to_owned(),
+        cache_path: PathBuf::from(".test_from_token_with_config.json"),
+        ..Default::default()
+    };
+
+    let spotify = AuthCodeSpotify::from_token_with_config(
+        tok.clone(),
+        creds.clone(),
+        oauth.clone(),
+        config.clone(),
+    );
+    assert_eq!(spotify.token.unwrap().access_token, tok.access_token);
+    assert_eq!(spotify.creds.id, creds.id);
+    assert_eq!(spotify.oauth.redirect_uri, oauth.redirect_uri);
+    assert_eq!(spotify.config.prefix, config.prefix);
+    assert_eq!(spotify.config.cache_path, config.cache_path);
+
+    let spotify = AuthCodePkceSpotify::from_token_with_config(
+        tok.clone(),
+        creds.clone(),
+        oauth.clone(),
+        config.clone(),
+    );
+    assert_eq!(spotify.token.unwrap().access_token, tok.access_token);
+    assert_eq!(spotify.creds.id, creds.id);
+    assert_eq!(spotify.oauth.redirect_uri, oauth.redirect_uri);
+    assert_eq!(spotify.config.prefix, config.prefix);
+    assert_eq!(spotify.config.cache_path, config.cache_path);
+
+    let spotify =
+        ClientCredsSpotify::from_token_with_config(tok.clone(), creds.clone(), config.clone());
+    assert_eq!(spotify.token.unwrap().access_token, tok.access_token);
+    assert_eq!(spotify.creds.id, creds.id);
+    assert_eq!(spotify.config.prefix, config.prefix);
+    assert_eq!(spotify.config.cache_path, config.cache_path);
+}
+
 #[test]
 fn test_token_is_expired() {
     let tok = Token {
@@ -123,6 +316,94 @@ fn test_token_is_expired() {
     assert!(tok.is_expired());
 }
 
+#[test]
+fn test_token_is_expired_with_margin() {
+    let tok = Token {
+        access_token: "test-access_token".to_owned(),
+        expires_in: Duration::seconds(5),
+        expires_at: Some(Utc::now() + Duration::seconds(5)),
+        ..Default::default()
+    };
+    assert!(!tok.is_expired());
+    assert!(!tok.is_expired_with_margin(Duration::seconds(2)));
+    assert!(tok.is_expired_with_margin(Duration::seconds(10)));
+    assert!(tok.is_expired_with_margin(Config::default().token_expiry_margin));
+}
+
+#[test]
+fn test_token_expires_in_seconds() {
+    let tok = Token {
+        expires_at: Some(Utc::now() + Duration::seconds(3600)),
+        ..Default::default()
+    };
+    let seconds = tok.expires_in_seconds().unwrap();
+    assert!((3599..=3600).contains(&seconds));
+
+    let tok = Token {
+        expires_at: Some(Utc::now() - Duration::seconds(60)),
+        ..Default::default()
+    };
+    let seconds = tok.expires_in_seconds().unwrap();
+    assert!((-61..=-60).contains(&seconds));
+
+    let tok = Token {
+        expires_at: None,
+        ..Default::default()
+    };
+    assert_eq!(tok.expires_in_seconds(), None);
+}
+
+#[test]
+fn test_token_scopes() {
+    let tok = Token {
+        scopes: scopes!("user-library-read", "user-follow-read"),
+        ..Default::default()
+    };
+
+    assert!(tok.has_scopes(&scopes!("user-library-read")));
+    assert!(tok.has_scopes(&scopes!("user-library-read", "user-follow-read")));
+    assert!(!tok.has_scopes(&scopes!("user-library-read", "user-library-modify")));
+    assert_eq!(
+        tok.missing_scopes(&scopes!("user-library-read", "user-library-modify")),
+        scopes!("user-library-modify")
+    );
+    assert!(tok.missing_scopes(&scopes!("user-follow-read")).is_empty());
+}
+
+#[test]
+fn test_token_from_spotipy_cache() {
+    let tok = Token::from_spotipy_cache("tests/fixtures/spotipy_cache.json").unwrap();
+    assert_eq!(
+        tok.access_token,
+        "BQDnKdG0A4zIF3Sb7QGs8e0GQFU5Ck2zcbl7OBmsfa5wY0Gd8T7Ob"
+    );
+    assert_eq!(tok.expires_in, Duration::seconds(3600));
+    assert_eq!(tok.expires_at.map(|x| x.timestamp()), Some(1640995200));
+    assert_eq!(
+        tok.refresh_token.as_deref(),
+        Some("AQBP7ym6Tf3cEQ3p5z2eGX1MGPn7ihzPo8C7Ex7nA6b9")
+    );
+    assert_eq!(
+        tok.scopes,
+        scopes!("user-library-read", "playlist-read-private")
+    );
+    assert!(tok.is_expired());
+
+    // The files written by rspotify aren't in the same format
+    let path = PathBuf::from(".test_token_from_spotipy_cache.json");
+    Token::default().write_cache(&path).unwrap();
+    assert!(matches!(
+        Token::from_spotipy_cache(&path),
+        Err(ClientError::ParseJson(_))
+    ));
+    fs::remove_file(&path).unwrap();
+
+    assert!(matches!(
+        Token::from_spotipy_cache("tests/fixtures/missing.json"),
+        Err(ClientError::Io(_))
+    ));
+}
+
 #[test]
 fn test_parse_response_code() {
     let spotify = AuthCodeSpotify::default();
@@ -143,3 +424,34 @@ fn test_parse_response_code() {
     let code = spotify.parse_response_code(url);
     assert_eq!(code, Some("AQD0yXvFEOvw".to_string()));
 }
+
+#[test]
+fn test_parse_response_code_and_verify_state() {
+    let oauth = OAuth {
+        state: "sN".to_owned(),
+        ..Default::default()
+    };
+    let spotify = AuthCodeSpotify::new(Credentials::default(), oauth);
+
+    let url = "http://localhost:8888/callback?code=AQD0yXvFEOvw&state=sN#_=_";
+    let code = spotify.parse_response_code_and_verify_state(url).unwrap();
+    assert_eq!(code, "AQD0yXvFEOvw");
+
+    let url = "http://localhost:8888/callback?code=AQD0yXvFEOvw&state=other";
+    let err = spotify
+        .parse_response_code_and_verify_state(url)
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InvalidAuth(_)));
+
+    let url = "http://localhost:8888/callback?code=AQD0yXvFEOvw";
+    let err = spotify
+        .parse_response_code_and_verify_state(url)
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InvalidAuth(_)));
+
+    let url = "http://localhost:8888/callback?state=sN";
+    let err = spotify
+        .parse_response_code_and_verify_state(url)
+        .unwrap_err();
+    assert!(matches!(err, ClientError::InvalidAuth(_)));
+}
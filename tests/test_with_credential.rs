@@ -157,7 +157,7 @@ async fn test_existing_playlist() {
 async fn test_fake_playlist() {
     let playlist = creds_client()
         .await
-        .playlist(Id::from_id("fakeid").unwrap(), None, None)
+        .playlist(Id::from_id("0000000000000000000000").unwrap(), None, None)
         .await;
     assert!(!playlist.is_ok());
 }
@@ -16,8 +16,9 @@
 
 use rspotify::{
     model::{
-        Country, EpisodeId, Id, Market, Offset, RepeatState, SearchType, ShowId, TimeRange,
-        TrackId, TrackPositions,
+        Country, EpisodeId, Id, Market, PlayableOffset, RecommendationsAttribute,
+        RecommendationsAttributes, RepeatState, SearchType, ShowId, TimeRange, TrackId,
+        TrackPositions,
     },
     prelude::*,
     scopes, AuthCodeSpotify, Credentials, OAuth, Token,
@@ -25,7 +26,6 @@ use rspotify::{
 
 use chrono::prelude::*;
 use maybe_async::maybe_async;
-use serde_json::map::Map;
 use std::env;
 
 /// Generating a new OAuth client for the requests.
@@ -139,7 +139,7 @@ async fn test_current_playing() {
 async fn test_current_user_followed_artists() {
     oauth_client()
         .await
-        .current_user_followed_artists(None, Some(10))
+        .current_user_followed_artists_manual(None, Some(10))
         .await
         .unwrap();
 }
@@ -169,7 +169,7 @@ async fn test_current_user_playlists() {
 async fn test_current_user_recently_played() {
     oauth_client()
         .await
-        .current_user_recently_played(Some(10))
+        .current_user_recently_played(Some(10), None)
         .await
         .unwrap();
 }
@@ -368,18 +368,17 @@ async fn test_previous_playback() {
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
 #[ignore]
 async fn test_recommendations() {
-    let mut payload = Map::new();
     let seed_artists = vec![Id::from_id("4NHQUGzhtTLFvgF5SZesLK").unwrap()];
     let seed_tracks = vec![Id::from_id("0c6xIDDpzE81m2q797ordA").unwrap()];
-    payload.insert("min_energy".to_owned(), 0.4.into());
-    payload.insert("min_popularity".to_owned(), 50.into());
+    let attributes = RecommendationsAttributes::new()
+        .seed_artists(seed_artists)
+        .seed_tracks(seed_tracks)
+        .min(RecommendationsAttribute::Energy(0.4))
+        .min(RecommendationsAttribute::Popularity(50));
     oauth_client()
         .await
         .recommendations(
-            &payload,
-            Some(seed_artists),
-            None::<Vec<&str>>,
-            Some(seed_tracks),
+            &attributes,
             Some(&Market::Country(Country::UnitedStates)),
             Some(10),
         )
@@ -464,8 +463,12 @@ async fn test_search_track() {
 
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
 #[ignore]
-async fn test_seek_track() {
-    oauth_client().await.seek_track(25000, None).await.unwrap();
+async fn test_seek_position() {
+    oauth_client()
+        .await
+        .seek_position(25000, None)
+        .await
+        .unwrap();
 }
 
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
@@ -481,7 +484,12 @@ async fn test_start_playback() {
     let uris = vec![TrackId::from_uri("spotify:track:4iV5W9uYEdYUVa79Axb7Rh").unwrap()];
     oauth_client()
         .await
-        .start_uris_playback(uris, Some(device_id), Some(Offset::for_position(0)), None)
+        .start_uris_playback(
+            uris,
+            Some(device_id),
+            Some(PlayableOffset::for_position(0)),
+            None,
+        )
         .await
         .unwrap();
 }
@@ -568,7 +576,7 @@ async fn test_playlist_add_tracks() {
 #[maybe_async::test(feature = "__sync", async(feature = "__async", tokio::test))]
 #[ignore]
 async fn test_playlist_change_detail() {
-    let playlist_id = "5jAOgWXCBKuinsGiZxjDQ5";
+    let playlist_id = Id::from_id("5jAOgWXCBKuinsGiZxjDQ5").unwrap();
     let playlist_name = "A New Playlist-update";
     oauth_client()
         .await